@@ -0,0 +1,221 @@
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+
+const MAX_ITEMS_PER_LEAF: usize = 4;
+
+#[derive(Debug)]
+enum BvhNodeContent {
+    Children(usize, usize),
+    Items(usize, usize),
+}
+
+#[derive(Debug)]
+struct BvhNode {
+    bounding_box: BoundingBox,
+    content: BvhNodeContent,
+}
+
+/// Bounding volume hierarchy over a set of items, each given by its bounding box.
+/// Queries report the indices of the items in the input slice. Items whose box has a NaN
+/// coordinate, such as the box of a zero-length cylinder, overlap nothing and are left out, since
+/// they would otherwise spread NaN into the boxes of the nodes and hide their neighbors
+#[derive(Debug)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    item_indices: Vec<usize>,
+}
+
+impl Bvh {
+    pub fn new(bounding_boxes: &[BoundingBox]) -> Bvh {
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            item_indices: (0..bounding_boxes.len())
+                .filter(|&item| !bounding_boxes[item].has_nan())
+                .collect(),
+        };
+
+        if !bvh.item_indices.is_empty() {
+            bvh.build_node(bounding_boxes, 0, bvh.item_indices.len());
+        }
+
+        bvh
+    }
+
+    /// Builds the node covering `item_indices[start..end]`, returning its index in `nodes`
+    fn build_node(&mut self, bounding_boxes: &[BoundingBox], start: usize, end: usize) -> usize {
+        let mut node_box: BoundingBox = Default::default();
+        let mut center_box: BoundingBox = Default::default();
+        for &item in &self.item_indices[start..end] {
+            node_box.add_box(&bounding_boxes[item]);
+            center_box.add_point(&bounding_boxes[item].get_center());
+        }
+
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounding_box: node_box,
+            content: BvhNodeContent::Items(start, end),
+        });
+
+        if end - start <= MAX_ITEMS_PER_LEAF {
+            return node_index;
+        }
+
+        let extent = center_box.max - center_box.min;
+        let split_dim = extent.imax();
+        let middle = start + (end - start) / 2;
+
+        self.item_indices[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            let center_a = bounding_boxes[*a].get_center()[split_dim];
+            let center_b = bounding_boxes[*b].get_center()[split_dim];
+            center_a.total_cmp(&center_b)
+        });
+
+        let left = self.build_node(bounding_boxes, start, middle);
+        let right = self.build_node(bounding_boxes, middle, end);
+        self.nodes[node_index].content = BvhNodeContent::Children(left, right);
+
+        node_index
+    }
+
+    /// Calls `visit` for every item whose bounding box might pass `node_filter`,
+    /// i.e. every item in a leaf where the filter accepted all enclosing nodes
+    fn visit_items(
+        &self,
+        node_filter: impl Fn(&BoundingBox) -> bool,
+        mut visit: impl FnMut(usize),
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node_filter(&node.bounding_box) {
+                continue;
+            }
+
+            match node.content {
+                BvhNodeContent::Children(left, right) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                BvhNodeContent::Items(start, end) => {
+                    self.item_indices[start..end]
+                        .iter()
+                        .for_each(|&item| visit(item));
+                }
+            }
+        }
+    }
+
+    /// Calls `visit` with the index of every item whose leaf is hit by the ray
+    pub fn for_each_hit_by_ray(
+        &self,
+        origin: &DVec3,
+        inverse_direction: &DVec3,
+        visit: impl FnMut(usize),
+    ) {
+        self.visit_items(
+            |node_box| node_box.intersects_ray(origin, inverse_direction),
+            visit,
+        );
+    }
+
+    /// Calls `visit` with the index of every item whose leaf overlaps the bounding box
+    pub fn for_each_overlapping(&self, bounding_box: &BoundingBox, visit: impl FnMut(usize)) {
+        self.visit_items(|node_box| node_box.overlaps(bounding_box), visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::Bvh;
+    use crate::linalg::BoundingBox;
+
+    fn create_box_grid() -> Vec<BoundingBox> {
+        (0..10)
+            .flat_map(|x| {
+                (0..10).map(move |y| BoundingBox {
+                    min: vec3(x as f64, y as f64, 0.0),
+                    max: vec3(x as f64 + 0.5, y as f64 + 0.5, 0.5),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ray_query_reports_items_along_ray() {
+        let boxes = create_box_grid();
+        let bvh = Bvh::new(&boxes);
+
+        let origin = vec3(-1.0, 3.25, 0.25);
+        let direction = vec3(1.0, 0.0, 0.0);
+        let inverse_direction = vec3(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+
+        let mut found = vec![];
+        bvh.for_each_hit_by_ray(&origin, &inverse_direction, |item| {
+            if boxes[item].intersects_ray(&origin, &inverse_direction) {
+                found.push(item);
+            }
+        });
+
+        assert_eq!(found.len(), 10);
+    }
+
+    #[test]
+    fn overlap_query_reports_all_overlapping_items() {
+        let boxes = create_box_grid();
+        let bvh = Bvh::new(&boxes);
+
+        let query_box = BoundingBox {
+            min: vec3(2.2, 2.2, 0.0),
+            max: vec3(4.2, 3.2, 1.0),
+        };
+
+        let mut found = vec![];
+        bvh.for_each_overlapping(&query_box, |item| {
+            if boxes[item].overlaps(&query_box) {
+                found.push(item);
+            }
+        });
+        found.sort_unstable();
+
+        let expected: Vec<usize> = (0..boxes.len())
+            .filter(|&item| boxes[item].overlaps(&query_box))
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(found.len(), 6);
+    }
+
+    #[test]
+    fn boxes_with_nan_do_not_hide_other_items() {
+        let mut boxes = create_box_grid();
+        boxes.push(BoundingBox {
+            min: vec3(f64::NAN, 0.0, 0.0),
+            max: vec3(f64::NAN, 1.0, 1.0),
+        });
+        let bvh = Bvh::new(&boxes);
+
+        let query_box = BoundingBox {
+            min: vec3(-1.0, -1.0, -1.0),
+            max: vec3(11.0, 11.0, 1.0),
+        };
+        let mut found = vec![];
+        bvh.for_each_overlapping(&query_box, |item| found.push(item));
+        found.sort_unstable();
+
+        assert_eq!(found, (0..boxes.len() - 1).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_bvh_reports_nothing() {
+        let bvh = Bvh::new(&[]);
+        bvh.for_each_hit_by_ray(&vec3(0.0, 0.0, 0.0), &vec3(1.0, 1.0, 1.0), |_| {
+            panic!("Empty BVH should not report items")
+        });
+    }
+}
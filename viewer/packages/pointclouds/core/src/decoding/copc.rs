@@ -0,0 +1,351 @@
+use std::io::Cursor;
+
+use laz::laszip::{ChunkTable, ChunkTableEntry};
+use laz::LazVlr;
+use nalgebra_glm::{vec3, DVec3};
+
+use crate::decoding::las::{decode_point_records, parse_header, parse_vlrs, LasHeader};
+use crate::decoding::laz::{decompress_records, find_laz_vlr};
+use crate::decoding::{read_bytes, DecodedPoints};
+use crate::linalg::BoundingBox;
+
+const COPC_USER_ID: &str = "copc";
+const COPC_INFO_RECORD_ID: u16 = 1;
+const HIERARCHY_ENTRY_SIZE: usize = 32;
+/// Node sizes are halved at each depth, so deeper nodes would be smaller than a double can hold
+const MAX_DEPTH: i32 = 64;
+
+/// Contents of the COPC info VLR, which must be the first VLR of a COPC file
+#[derive(Clone, Debug)]
+pub struct CopcInfo {
+    /// Center of the root octree node
+    pub center: DVec3,
+    /// Half the side length of the cubic root octree node
+    pub halfsize: f64,
+    pub spacing: f64,
+    pub root_hierarchy_offset: u64,
+    pub root_hierarchy_size: u64,
+}
+
+impl CopcInfo {
+    fn try_parse(data: &[u8]) -> Result<Self, String> {
+        let read_f64 = |offset| read_bytes(data, offset).map(f64::from_le_bytes);
+        let read_u64 = |offset| read_bytes(data, offset).map(u64::from_le_bytes);
+
+        Ok(CopcInfo {
+            center: vec3(read_f64(0)?, read_f64(8)?, read_f64(16)?),
+            halfsize: read_f64(24)?,
+            spacing: read_f64(32)?,
+            root_hierarchy_offset: read_u64(40)?,
+            root_hierarchy_size: read_u64(48)?,
+        })
+    }
+
+    /// Bounds of the octree node with the given key
+    pub fn node_bounds(&self, key: &CopcKey) -> BoundingBox {
+        let root_min = self.center.add_scalar(-self.halfsize);
+        let node_size = 2.0 * self.halfsize * 0.5f64.powi(key.depth);
+        let min = root_min + vec3(key.x as f64, key.y as f64, key.z as f64) * node_size;
+
+        BoundingBox {
+            min,
+            max: min.add_scalar(node_size),
+        }
+    }
+}
+
+/// Octree node key, using the same depth-x-y-z scheme as EPT
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CopcKey {
+    pub depth: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl CopcKey {
+    /// The name of the node, as used for EPT files and `EptKey.name()`
+    pub fn name(&self) -> String {
+        format!("{}-{}-{}-{}", self.depth, self.x, self.y, self.z)
+    }
+}
+
+/// One entry of a COPC hierarchy page. Depending on `point_count`, the byte range either holds
+/// the compressed points of the node, or another hierarchy page
+#[derive(Clone, Debug, PartialEq)]
+pub struct CopcHierarchyEntry {
+    pub key: CopcKey,
+    /// Absolute offset in the file
+    pub offset: u64,
+    pub byte_size: u32,
+    /// Number of points in the node, 0 for empty nodes and -1 for nodes pointing to a child
+    /// hierarchy page
+    pub point_count: i32,
+}
+
+impl CopcHierarchyEntry {
+    pub fn is_child_page(&self) -> bool {
+        self.point_count == -1
+    }
+}
+
+/// Reads the hierarchy and point chunks of a COPC file, which are fetched separately by byte
+/// range after the header
+pub struct CopcReader {
+    header: LasHeader,
+    laz_vlr: LazVlr,
+    info: CopcInfo,
+}
+
+impl CopcReader {
+    /// Creates the reader from the start of the file, which must contain the header and all VLRs
+    pub fn try_new(buffer: &[u8]) -> Result<Self, String> {
+        let header = parse_header(buffer)?;
+
+        let info = parse_vlrs(buffer, &header)?
+            .into_iter()
+            .find(|vlr| vlr.user_id == COPC_USER_ID && vlr.record_id == COPC_INFO_RECORD_ID)
+            .ok_or_else(|| "COPC file is missing the COPC info variable length record".to_string())
+            .and_then(|vlr| CopcInfo::try_parse(vlr.data))?;
+        let laz_vlr = find_laz_vlr(buffer, &header)?;
+
+        Ok(CopcReader {
+            header,
+            laz_vlr,
+            info,
+        })
+    }
+
+    pub fn info(&self) -> &CopcInfo {
+        &self.info
+    }
+
+    /// Parses a hierarchy page, starting with the root page at `info().root_hierarchy_offset`
+    pub fn parse_hierarchy_page(&self, page: &[u8]) -> Result<Vec<CopcHierarchyEntry>, String> {
+        if !page.len().is_multiple_of(HIERARCHY_ENTRY_SIZE) {
+            return Err(format!(
+                "COPC hierarchy page size {} is not a multiple of the entry size {}",
+                page.len(),
+                HIERARCHY_ENTRY_SIZE
+            ));
+        }
+
+        page.chunks_exact(HIERARCHY_ENTRY_SIZE)
+            .map(|entry| {
+                let read_i32 = |offset| read_bytes(entry, offset).map(i32::from_le_bytes);
+
+                let depth = read_i32(0)?;
+                if !(0..MAX_DEPTH).contains(&depth) {
+                    return Err(format!("Invalid COPC hierarchy entry depth {}", depth));
+                }
+
+                Ok(CopcHierarchyEntry {
+                    key: CopcKey {
+                        depth,
+                        x: read_i32(4)?,
+                        y: read_i32(8)?,
+                        z: read_i32(12)?,
+                    },
+                    offset: read_bytes(entry, 16).map(u64::from_le_bytes)?,
+                    byte_size: read_i32(24)? as u32,
+                    point_count: read_i32(28)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Decodes the compressed points of a node, given the bytes in its range.
+    /// Positions are `value * scale + offset - mins`, using the scale and offset in the LAS header
+    pub fn decode_chunk(
+        &self,
+        chunk: &[u8],
+        point_count: u32,
+        mins: &DVec3,
+    ) -> Result<DecodedPoints, String> {
+        // Each COPC node is a single LAZ chunk, so we wrap it as point data with a chunk table
+        // holding only that chunk
+        let mut chunk_table = ChunkTable::with_capacity(1);
+        chunk_table.push(ChunkTableEntry {
+            point_count: point_count as u64,
+            byte_count: chunk.len() as u64,
+        });
+
+        let chunk_table_offset = (ChunkTable::OFFSET_SIZE + chunk.len()) as i64;
+        let mut point_data = chunk_table_offset.to_le_bytes().to_vec();
+        point_data.extend_from_slice(chunk);
+        chunk_table
+            .write_to(&mut point_data, &self.laz_vlr)
+            .map_err(|e| format!("Failed to create COPC chunk table: {}", e))?;
+
+        let records = decompress_records(
+            Cursor::new(&point_data[..]),
+            self.laz_vlr.clone(),
+            point_count as u64,
+        )?;
+
+        decode_point_records(&records, &self.header, mins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use laz::{LasZipCompressor, LazVlr, LazVlrBuilder};
+    use nalgebra_glm::vec3;
+
+    use super::{CopcHierarchyEntry, CopcKey, CopcReader};
+    use crate::decoding::test_utils::{
+        create_laszip_vlr, create_laz_header_and_vlrs, create_vlr, TestLasHeader,
+    };
+
+    fn create_laz_vlr() -> LazVlr {
+        LazVlrBuilder::default()
+            .with_point_format(6, 0)
+            .unwrap()
+            .with_variable_chunk_size()
+            .build()
+    }
+
+    fn create_header_and_vlrs() -> Vec<u8> {
+        let mut copc_info = vec![];
+        [10.0f64, 20.0, 30.0, 8.0, 1.0]
+            .iter()
+            .for_each(|v| copc_info.extend(v.to_le_bytes()));
+        copc_info.extend(1000u64.to_le_bytes());
+        copc_info.extend(64u64.to_le_bytes());
+        copc_info.extend([0; 104]);
+
+        let header = TestLasHeader {
+            minor_version: 4,
+            point_format: 6,
+            record_length: 30,
+            number_of_points: 0,
+            scale: [0.01; 3],
+            offset: [0.0; 3],
+        };
+        create_laz_header_and_vlrs(
+            &header,
+            &[
+                create_vlr("copc", 1, &copc_info),
+                create_laszip_vlr(&create_laz_vlr()),
+            ],
+        )
+    }
+
+    /// Point format 6 record: xyz, intensity, return bits, flags, classification, user data,
+    /// scan angle, point source id and gps time
+    fn create_record(xyz: [i32; 3], intensity: u16, class: u8) -> Vec<u8> {
+        let mut record = vec![];
+        xyz.iter().for_each(|v| record.extend(v.to_le_bytes()));
+        record.extend(intensity.to_le_bytes());
+        record.extend([0x11, 0, class, 0, 0, 0]);
+        record.extend(3u16.to_le_bytes());
+        record.extend(0.0f64.to_le_bytes());
+        record
+    }
+
+    fn compress_chunk(records: &[u8]) -> Vec<u8> {
+        let mut compressor = LasZipCompressor::new(Cursor::new(vec![]), create_laz_vlr()).unwrap();
+        compressor.compress_many(records).unwrap();
+        compressor.done().unwrap();
+        let compressed = compressor.into_inner().into_inner();
+
+        let chunk_table_offset = i64::from_le_bytes(compressed[..8].try_into().unwrap());
+        compressed[8..chunk_table_offset as usize].to_vec()
+    }
+
+    fn create_hierarchy_entry(key: [i32; 4], offset: u64, byte_size: i32, count: i32) -> Vec<u8> {
+        let mut entry = vec![];
+        key.iter().for_each(|v| entry.extend(v.to_le_bytes()));
+        entry.extend(offset.to_le_bytes());
+        entry.extend(byte_size.to_le_bytes());
+        entry.extend(count.to_le_bytes());
+        entry
+    }
+
+    #[test]
+    fn copc_info_is_read_from_vlr() {
+        let reader = CopcReader::try_new(&create_header_and_vlrs()).unwrap();
+
+        let info = reader.info();
+        assert_eq!(info.center, vec3(10.0, 20.0, 30.0));
+        assert_eq!(info.halfsize, 8.0);
+        assert_eq!(info.root_hierarchy_offset, 1000);
+        assert_eq!(info.root_hierarchy_size, 64);
+
+        let bounds = info.node_bounds(&CopcKey {
+            depth: 2,
+            x: 1,
+            y: 0,
+            z: 3,
+        });
+        assert_eq!(bounds.min, vec3(6.0, 12.0, 34.0));
+        assert_eq!(bounds.max, vec3(10.0, 16.0, 38.0));
+    }
+
+    #[test]
+    fn hierarchy_page_entries_are_parsed() {
+        let reader = CopcReader::try_new(&create_header_and_vlrs()).unwrap();
+        let mut page = create_hierarchy_entry([0, 0, 0, 0], 2000, 150, 12);
+        page.extend(create_hierarchy_entry([1, 1, 0, 1], 3000, 64, -1));
+
+        let entries = reader.parse_hierarchy_page(&page).unwrap();
+
+        assert_eq!(
+            entries[0],
+            CopcHierarchyEntry {
+                key: CopcKey {
+                    depth: 0,
+                    x: 0,
+                    y: 0,
+                    z: 0
+                },
+                offset: 2000,
+                byte_size: 150,
+                point_count: 12,
+            }
+        );
+        assert_eq!(entries[1].key.name(), "1-1-0-1");
+        assert!(entries[1].is_child_page());
+        assert!(reader.parse_hierarchy_page(&page[..40]).is_err());
+    }
+
+    #[test]
+    fn hierarchy_entries_with_invalid_depth_are_rejected() {
+        let reader = CopcReader::try_new(&create_header_and_vlrs()).unwrap();
+
+        for depth in [-1, 64, i32::MAX] {
+            let page = create_hierarchy_entry([depth, 0, 0, 0], 2000, 150, 12);
+            assert!(reader.parse_hierarchy_page(&page).is_err());
+        }
+    }
+
+    #[test]
+    fn chunk_points_are_decoded_from_byte_range() {
+        let reader = CopcReader::try_new(&create_header_and_vlrs()).unwrap();
+        let records = [
+            create_record([100, 200, 300], 5, 2),
+            create_record([-100, 0, 100], 9, 7),
+            create_record([0, 50, 0], 1, 1),
+        ]
+        .concat();
+        let chunk = compress_chunk(&records);
+
+        let points = reader
+            .decode_chunk(&chunk, 3, &vec3(1.0, 1.0, 1.0))
+            .unwrap();
+
+        assert_eq!(points.num_points(), 3);
+        assert_eq!(
+            points.position,
+            vec![0.0, 1.0, 2.0, -2.0, -1.0, 0.0, -1.0, -0.5, -1.0]
+        );
+        assert_eq!(points.intensity, Some(vec![5.0, 9.0, 1.0]));
+        assert_eq!(points.classification, Some(vec![2, 7, 1]));
+        assert_eq!(points.return_number, Some(vec![1, 1, 1]));
+        assert_eq!(points.number_of_returns, Some(vec![1, 1, 1]));
+        assert_eq!(points.point_source_id, Some(vec![3, 3, 3]));
+        assert_eq!(points.color, None);
+    }
+}
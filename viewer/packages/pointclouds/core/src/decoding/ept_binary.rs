@@ -0,0 +1,300 @@
+use nalgebra_glm::{vec3, DVec3};
+
+use crate::decoding::DecodedPoints;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttributeType {
+    Signed,
+    Unsigned,
+    Float,
+}
+
+/// One dimension of the point records in an EPT binary tile
+#[derive(Clone, Debug)]
+pub struct AttributeSchema {
+    pub name: String,
+    pub size: usize,
+    pub attribute_type: AttributeType,
+}
+
+/// How to interpret an EPT binary tile, see `EptInputData` in the TypeScript workers.
+/// Decoded positions are `value * scale + offset - mins`
+#[derive(Clone, Debug)]
+pub struct EptLayout {
+    pub schema: Vec<AttributeSchema>,
+    pub scale: DVec3,
+    pub offset: DVec3,
+    pub mins: DVec3,
+}
+
+/// Reads one little-endian attribute from a point record
+struct AttributeReader {
+    offset: usize,
+    size: usize,
+    attribute_type: AttributeType,
+}
+
+impl AttributeReader {
+    /// Finds the attribute in the schema. Fails if it has an unsupported size
+    fn try_find(schema: &[AttributeSchema], name: &str) -> Result<Option<Self>, String> {
+        let mut offset = 0;
+        for attribute in schema {
+            if attribute.name == name {
+                let valid_sizes: &[usize] = match attribute.attribute_type {
+                    AttributeType::Signed | AttributeType::Unsigned => &[1, 2, 4],
+                    AttributeType::Float => &[4, 8],
+                };
+                if !valid_sizes.contains(&attribute.size) {
+                    return Err(format!(
+                        "Unrecognized byte size {} for \"{:?}\" attribute {}",
+                        attribute.size, attribute.attribute_type, name
+                    ));
+                }
+
+                return Ok(Some(AttributeReader {
+                    offset,
+                    size: attribute.size,
+                    attribute_type: attribute.attribute_type,
+                }));
+            }
+            offset += attribute.size;
+        }
+
+        Ok(None)
+    }
+
+    fn try_find_required(schema: &[AttributeSchema], name: &str) -> Result<Self, String> {
+        AttributeReader::try_find(schema, name)?
+            .ok_or_else(|| format!("EPT schema is missing the {} attribute", name))
+    }
+
+    fn read(&self, record: &[u8]) -> f64 {
+        let bytes = &record[self.offset..self.offset + self.size];
+
+        match (self.attribute_type, self.size) {
+            (AttributeType::Signed, 1) => bytes[0] as i8 as f64,
+            (AttributeType::Signed, 2) => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            (AttributeType::Signed, _) => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+            }
+            (AttributeType::Unsigned, 1) => bytes[0] as f64,
+            (AttributeType::Unsigned, 2) => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            (AttributeType::Unsigned, _) => {
+                u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+            }
+            (AttributeType::Float, 4) => {
+                f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64
+            }
+            (AttributeType::Float, _) => f64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]),
+        }
+    }
+}
+
+/// Decodes an uncompressed EPT binary tile. Colors are scaled down to 8 bits if any
+/// component in the tile is larger than 255
+pub fn decode_ept_binary(buffer: &[u8], layout: &EptLayout) -> Result<DecodedPoints, String> {
+    let schema = &layout.schema;
+    let point_size: usize = schema.iter().map(|attribute| attribute.size).sum();
+
+    if point_size == 0 || buffer.is_empty() {
+        return Err(format!(
+            "Invalid buffer: pointSize={}, byteLength={}",
+            point_size,
+            buffer.len()
+        ));
+    }
+
+    let num_points = buffer.len() / point_size;
+    if num_points == 0 {
+        return Err(format!(
+            "Buffer too small: {} bytes for point size {}",
+            buffer.len(),
+            point_size
+        ));
+    }
+
+    let xyz_readers = [
+        AttributeReader::try_find_required(schema, "X")?,
+        AttributeReader::try_find_required(schema, "Y")?,
+        AttributeReader::try_find_required(schema, "Z")?,
+    ];
+    let rgb_readers = match (
+        AttributeReader::try_find(schema, "Red")?,
+        AttributeReader::try_find(schema, "Green")?,
+        AttributeReader::try_find(schema, "Blue")?,
+    ) {
+        (Some(red), Some(green), Some(blue)) => Some([red, green, blue]),
+        _ => None,
+    };
+    let intensity_reader = AttributeReader::try_find(schema, "Intensity")?;
+    let classification_reader = AttributeReader::try_find(schema, "Classification")?;
+    let return_number_reader = AttributeReader::try_find(schema, "ReturnNumber")?;
+    let number_of_returns_reader = AttributeReader::try_find(schema, "NumberOfReturns")?;
+    let point_source_id_reader = AttributeReader::try_find(schema, "PointSourceId")?;
+
+    let records = || buffer.chunks_exact(point_size);
+
+    let two_byte_color = rgb_readers.as_ref().is_some_and(|readers| {
+        records().any(|record| readers.iter().any(|reader| reader.read(record) > 255.0))
+    });
+    let color_divisor = if two_byte_color { 256.0 } else { 1.0 };
+
+    let mut points = DecodedPoints {
+        position: Vec::with_capacity(3 * num_points),
+        color: rgb_readers
+            .as_ref()
+            .map(|_| Vec::with_capacity(4 * num_points)),
+        intensity: intensity_reader
+            .as_ref()
+            .map(|_| Vec::with_capacity(num_points)),
+        classification: classification_reader
+            .as_ref()
+            .map(|_| Vec::with_capacity(num_points)),
+        return_number: return_number_reader
+            .as_ref()
+            .map(|_| Vec::with_capacity(num_points)),
+        number_of_returns: number_of_returns_reader
+            .as_ref()
+            .map(|_| Vec::with_capacity(num_points)),
+        point_source_id: point_source_id_reader
+            .as_ref()
+            .map(|_| Vec::with_capacity(num_points)),
+        ..Default::default()
+    };
+
+    for record in records() {
+        let raw_position = vec3(
+            xyz_readers[0].read(record),
+            xyz_readers[1].read(record),
+            xyz_readers[2].read(record),
+        );
+        points.add_position(
+            &(raw_position.component_mul(&layout.scale) + layout.offset - layout.mins),
+        );
+
+        if let (Some(readers), Some(color)) = (&rgb_readers, &mut points.color) {
+            color.extend(
+                readers
+                    .iter()
+                    .map(|reader| (reader.read(record) / color_divisor) as u8),
+            );
+            color.push(0);
+        }
+        if let (Some(reader), Some(intensity)) = (&intensity_reader, &mut points.intensity) {
+            intensity.push(reader.read(record) as f32);
+        }
+        if let (Some(reader), Some(classification)) =
+            (&classification_reader, &mut points.classification)
+        {
+            classification.push(reader.read(record) as u8);
+        }
+        if let (Some(reader), Some(return_number)) =
+            (&return_number_reader, &mut points.return_number)
+        {
+            return_number.push(reader.read(record) as u8);
+        }
+        if let (Some(reader), Some(number_of_returns)) =
+            (&number_of_returns_reader, &mut points.number_of_returns)
+        {
+            number_of_returns.push(reader.read(record) as u8);
+        }
+        if let (Some(reader), Some(point_source_id)) =
+            (&point_source_id_reader, &mut points.point_source_id)
+        {
+            point_source_id.push(reader.read(record) as u16);
+        }
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::{decode_ept_binary, AttributeSchema, AttributeType, EptLayout};
+
+    fn attribute(name: &str, size: usize, attribute_type: AttributeType) -> AttributeSchema {
+        AttributeSchema {
+            name: name.to_string(),
+            size,
+            attribute_type,
+        }
+    }
+
+    fn create_layout() -> EptLayout {
+        EptLayout {
+            schema: vec![
+                attribute("X", 4, AttributeType::Signed),
+                attribute("Y", 4, AttributeType::Signed),
+                attribute("Z", 8, AttributeType::Float),
+                attribute("Intensity", 2, AttributeType::Unsigned),
+                attribute("Classification", 1, AttributeType::Unsigned),
+                attribute("Red", 2, AttributeType::Unsigned),
+                attribute("Green", 2, AttributeType::Unsigned),
+                attribute("Blue", 2, AttributeType::Unsigned),
+            ],
+            scale: vec3(0.01, 0.01, 1.0),
+            offset: vec3(100.0, 200.0, 0.0),
+            mins: vec3(100.0, 200.0, 10.0),
+        }
+    }
+
+    fn create_record(x: i32, y: i32, z: f64, intensity: u16, class: u8, rgb: [u16; 3]) -> Vec<u8> {
+        let mut record = vec![];
+        record.extend(x.to_le_bytes());
+        record.extend(y.to_le_bytes());
+        record.extend(z.to_le_bytes());
+        record.extend(intensity.to_le_bytes());
+        record.push(class);
+        rgb.iter().for_each(|c| record.extend(c.to_le_bytes()));
+        record
+    }
+
+    #[test]
+    fn records_are_decoded_with_scale_offset_and_mins() {
+        let mut buffer = create_record(150, -50, 12.0, 300, 2, [512, 0, 65535]);
+        buffer.extend(create_record(-250, 50, 14.0, 7, 6, [256, 1024, 0]));
+        buffer.push(0xff);
+
+        let points = decode_ept_binary(&buffer, &create_layout()).unwrap();
+
+        assert_eq!(points.num_points(), 2);
+        assert_eq!(points.position, vec![1.5, -0.5, 2.0, -2.5, 0.5, 4.0]);
+        assert_eq!(points.intensity, Some(vec![300.0, 7.0]));
+        assert_eq!(points.classification, Some(vec![2, 6]));
+        assert_eq!(points.color, Some(vec![2, 0, 255, 0, 1, 4, 0, 0]));
+        assert_eq!(points.return_number, None);
+        assert_eq!(points.point_source_id, None);
+
+        assert!((points.tight_bounding_box.min - vec3(-2.5, -0.5, 2.0)).magnitude() < 1e-9);
+        assert!((points.tight_bounding_box.max - vec3(1.5, 0.5, 4.0)).magnitude() < 1e-9);
+        assert!((points.mean() - vec3(-0.5, 0.0, 3.0)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn single_byte_colors_are_not_scaled() {
+        let buffer = create_record(0, 0, 10.0, 0, 0, [10, 20, 255]);
+
+        let points = decode_ept_binary(&buffer, &create_layout()).unwrap();
+
+        assert_eq!(points.color, Some(vec![10, 20, 255, 0]));
+    }
+
+    #[test]
+    fn invalid_buffers_and_schemas_are_rejected() {
+        let layout = create_layout();
+        assert!(decode_ept_binary(&[], &layout).is_err());
+        assert!(decode_ept_binary(&[0; 10], &layout).is_err());
+
+        let mut missing_z = create_layout();
+        missing_z.schema.remove(2);
+        assert!(decode_ept_binary(&[0; 100], &missing_z).is_err());
+
+        let mut three_byte_x = create_layout();
+        three_byte_x.schema[0].size = 3;
+        assert!(decode_ept_binary(&[0; 100], &three_byte_x).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::io::Read;
+
+use ruzstd::decoding::StreamingDecoder;
+
+use crate::decoding::{decode_ept_binary, DecodedPoints, EptLayout};
+
+/// Decodes a zstd-compressed EPT tile, which holds the same records as an EPT binary tile
+pub fn decode_ept_zstandard(buffer: &[u8], layout: &EptLayout) -> Result<DecodedPoints, String> {
+    let mut decoder =
+        StreamingDecoder::new(buffer).map_err(|e| format!("Invalid zstandard EPT tile: {}", e))?;
+
+    let mut decompressed = vec![];
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("Failed to decompress zstandard EPT tile: {}", e))?;
+
+    decode_ept_binary(&decompressed, layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+    use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+    use super::decode_ept_zstandard;
+    use crate::decoding::{AttributeSchema, AttributeType, EptLayout};
+
+    fn create_layout() -> EptLayout {
+        let attribute = |name: &str, size| AttributeSchema {
+            name: name.to_string(),
+            size,
+            attribute_type: AttributeType::Signed,
+        };
+
+        EptLayout {
+            schema: vec![attribute("X", 4), attribute("Y", 4), attribute("Z", 4)],
+            scale: vec3(0.5, 0.5, 0.5),
+            offset: vec3(0.0, 0.0, 0.0),
+            mins: vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn compressed_records_are_decoded_like_binary_tiles() {
+        let records: Vec<u8> = [[4i32, 6, 8], [-2, 0, 2]]
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let buffer = compress_to_vec(&records[..], CompressionLevel::Fastest);
+
+        let points = decode_ept_zstandard(&buffer, &create_layout()).unwrap();
+
+        assert_eq!(points.position, vec![1.0, 2.0, 3.0, -2.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn uncompressed_buffers_are_rejected() {
+        assert!(decode_ept_zstandard(&[0; 24], &create_layout()).is_err());
+    }
+}
@@ -0,0 +1,484 @@
+use nalgebra_glm::{vec3, DVec3};
+
+use crate::decoding::{create_color_buffer, read_bytes, DecodedPoints};
+use crate::linalg::BoundingBox;
+
+const FILE_SIGNATURE: &[u8; 4] = b"LASF";
+const VLR_HEADER_SIZE: usize = 54;
+const EVLR_HEADER_SIZE: usize = 60;
+/// Size of the LAS 1.4 public header, which adds the extended VLRs and 64-bit point counts
+const LAS_1_4_HEADER_SIZE: u16 = 375;
+
+/// The parts of the LAS public header needed for reading points, for LAS 1.0 to 1.4
+#[derive(Debug)]
+pub struct LasHeader {
+    pub version: (u8, u8),
+    pub header_size: u16,
+    pub offset_to_point_data: u32,
+    pub number_of_vlrs: u32,
+    /// Without the compression bits set by LAZ writers
+    pub point_data_format: u8,
+    /// Whether the points are LAZ-compressed
+    pub is_compressed: bool,
+    pub point_data_record_length: u16,
+    pub number_of_points: u64,
+    pub scale: DVec3,
+    pub offset: DVec3,
+    /// Bounds of the points, with scale and offset applied
+    pub bounding_box: BoundingBox,
+    /// Only set for LAS 1.4 files
+    pub start_of_first_evlr: Option<u64>,
+    pub number_of_evlrs: u32,
+}
+
+/// A variable length record, either following the public header or one of the extended
+/// records after the point data
+#[derive(Debug)]
+pub struct Vlr<'a> {
+    pub user_id: String,
+    pub record_id: u16,
+    pub data: &'a [u8],
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> Result<u16, String> {
+    read_bytes(buffer, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> Result<u32, String> {
+    read_bytes(buffer, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(buffer: &[u8], offset: usize) -> Result<u64, String> {
+    read_bytes(buffer, offset).map(u64::from_le_bytes)
+}
+
+fn read_dvec3(buffer: &[u8], offset: usize) -> Result<DVec3, String> {
+    let read_f64 = |offset| read_bytes(buffer, offset).map(f64::from_le_bytes);
+    Ok(vec3(
+        read_f64(offset)?,
+        read_f64(offset + 8)?,
+        read_f64(offset + 16)?,
+    ))
+}
+
+pub fn parse_header(buffer: &[u8]) -> Result<LasHeader, String> {
+    if read_bytes::<4>(buffer, 0)? != *FILE_SIGNATURE {
+        return Err("Not a LAS file, the file signature is missing".to_string());
+    }
+
+    let [major, minor] = read_bytes::<2>(buffer, 24)?;
+    if major != 1 || minor > 4 {
+        return Err(format!("Unsupported LAS version {}.{}", major, minor));
+    }
+
+    let version = (major, minor);
+    let header_size = read_u16(buffer, 94)?;
+    let point_data_format = read_bytes::<1>(buffer, 104)?[0];
+    let legacy_number_of_points = read_u32(buffer, 107)? as u64;
+
+    // LAS 1.4 moved the point count to a 64-bit field, keeping the legacy one at 0 when it
+    // does not fit or for the new point formats
+    let number_of_points = if version >= (1, 4) && legacy_number_of_points == 0 {
+        read_u64(buffer, 247)?
+    } else {
+        legacy_number_of_points
+    };
+
+    // The bounds are stored as max x, min x, max y, min y, max z, min z
+    let read_f64 = |offset| read_bytes(buffer, offset).map(f64::from_le_bytes);
+    let bounding_box = BoundingBox {
+        min: vec3(read_f64(187)?, read_f64(203)?, read_f64(219)?),
+        max: vec3(read_f64(179)?, read_f64(195)?, read_f64(211)?),
+    };
+
+    let (start_of_first_evlr, number_of_evlrs) =
+        if version >= (1, 4) && header_size >= LAS_1_4_HEADER_SIZE {
+            (Some(read_u64(buffer, 235)?), read_u32(buffer, 243)?)
+        } else {
+            (None, 0)
+        };
+
+    Ok(LasHeader {
+        version,
+        header_size,
+        offset_to_point_data: read_u32(buffer, 96)?,
+        number_of_vlrs: read_u32(buffer, 100)?,
+        point_data_format: point_data_format & 0x3f,
+        is_compressed: point_data_format & 0x80 != 0,
+        point_data_record_length: read_u16(buffer, 105)?,
+        number_of_points,
+        scale: read_dvec3(buffer, 131)?,
+        offset: read_dvec3(buffer, 155)?,
+        bounding_box,
+        start_of_first_evlr,
+        number_of_evlrs,
+    })
+}
+
+pub fn parse_vlrs<'a>(buffer: &'a [u8], header: &LasHeader) -> Result<Vec<Vlr<'a>>, String> {
+    // Each record takes at least its header, so the count is not trusted beyond that
+    let max_vlrs = buffer.len() / VLR_HEADER_SIZE;
+    let mut vlrs = Vec::with_capacity(max_vlrs.min(header.number_of_vlrs as usize));
+    let mut offset = header.header_size as usize;
+
+    for _ in 0..header.number_of_vlrs {
+        let user_id = read_bytes::<16>(buffer, offset + 2)?;
+        let record_id = read_u16(buffer, offset + 18)?;
+        let data_length = read_u16(buffer, offset + 20)? as usize;
+        let data_start = offset + VLR_HEADER_SIZE;
+
+        let data = buffer
+            .get(data_start..data_start + data_length)
+            .ok_or_else(|| "LAS variable length record is truncated".to_string())?;
+
+        vlrs.push(Vlr {
+            user_id: parse_user_id(&user_id),
+            record_id,
+            data,
+        });
+        offset = data_start + data_length;
+    }
+
+    Ok(vlrs)
+}
+
+/// Parses the extended variable length records after the point data, which only LAS 1.4
+/// files have
+pub fn parse_evlrs<'a>(buffer: &'a [u8], header: &LasHeader) -> Result<Vec<Vlr<'a>>, String> {
+    let Some(start_of_first_evlr) = header.start_of_first_evlr else {
+        return Ok(vec![]);
+    };
+
+    let truncated_error = || "LAS extended variable length record is truncated".to_string();
+    let max_evlrs = buffer.len() / EVLR_HEADER_SIZE;
+    let mut evlrs = Vec::with_capacity(max_evlrs.min(header.number_of_evlrs as usize));
+    let mut offset = usize::try_from(start_of_first_evlr).map_err(|_| truncated_error())?;
+
+    for _ in 0..header.number_of_evlrs {
+        let data_start = offset
+            .checked_add(EVLR_HEADER_SIZE)
+            .ok_or_else(truncated_error)?;
+        let evlr_header = buffer.get(offset..data_start).ok_or_else(truncated_error)?;
+
+        let user_id = read_bytes::<16>(evlr_header, 2)?;
+        let record_id = read_u16(evlr_header, 18)?;
+        let data_end = usize::try_from(read_u64(evlr_header, 20)?)
+            .ok()
+            .and_then(|data_length| data_start.checked_add(data_length))
+            .ok_or_else(truncated_error)?;
+        let data = buffer
+            .get(data_start..data_end)
+            .ok_or_else(truncated_error)?;
+
+        evlrs.push(Vlr {
+            user_id: parse_user_id(&user_id),
+            record_id,
+            data,
+        });
+        offset = data_end;
+    }
+
+    Ok(evlrs)
+}
+
+fn parse_user_id(user_id: &[u8]) -> String {
+    String::from_utf8_lossy(user_id)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// Byte offsets of the attributes that differ between LAS point formats
+struct PointRecordLayout {
+    /// Formats 6 to 10 have four bits for each return field, and a full byte for classification
+    is_extended: bool,
+    gps_time_offset: Option<usize>,
+    rgb_offset: Option<usize>,
+    near_infrared_offset: Option<usize>,
+    min_record_length: usize,
+}
+
+impl PointRecordLayout {
+    fn try_new(point_data_format: u8) -> Result<Self, String> {
+        let (is_extended, gps_time_offset, rgb_offset, near_infrared_offset, min_record_length) =
+            match point_data_format {
+                0 => (false, None, None, None, 20),
+                1 => (false, Some(20), None, None, 28),
+                2 => (false, None, Some(20), None, 26),
+                3 => (false, Some(20), Some(28), None, 34),
+                4 => (false, Some(20), None, None, 57),
+                5 => (false, Some(20), Some(28), None, 63),
+                6 => (true, Some(22), None, None, 30),
+                7 => (true, Some(22), Some(30), None, 36),
+                8 => (true, Some(22), Some(30), Some(36), 38),
+                9 => (true, Some(22), None, None, 59),
+                10 => (true, Some(22), Some(30), Some(36), 67),
+                _ => {
+                    return Err(format!(
+                        "Unsupported LAS point data format {}",
+                        point_data_format
+                    ))
+                }
+            };
+
+        Ok(PointRecordLayout {
+            is_extended,
+            gps_time_offset,
+            rgb_offset,
+            near_infrared_offset,
+            min_record_length,
+        })
+    }
+}
+
+/// Decodes uncompressed point records as described by the header.
+/// Positions are `value * scale + offset - mins`
+pub fn decode_point_records(
+    records: &[u8],
+    header: &LasHeader,
+    mins: &DVec3,
+) -> Result<DecodedPoints, String> {
+    let layout = PointRecordLayout::try_new(header.point_data_format)?;
+    let record_length = header.point_data_record_length as usize;
+    if record_length < layout.min_record_length {
+        return Err(format!(
+            "LAS point record length {} is too short for point data format {}",
+            record_length, header.point_data_format
+        ));
+    }
+
+    let num_points = records.len() / record_length;
+    let mut points = DecodedPoints {
+        position: Vec::with_capacity(3 * num_points),
+        intensity: Some(Vec::with_capacity(num_points)),
+        classification: Some(Vec::with_capacity(num_points)),
+        return_number: Some(Vec::with_capacity(num_points)),
+        number_of_returns: Some(Vec::with_capacity(num_points)),
+        point_source_id: Some(Vec::with_capacity(num_points)),
+        gps_time: layout
+            .gps_time_offset
+            .map(|_| Vec::with_capacity(num_points)),
+        near_infrared: layout
+            .near_infrared_offset
+            .map(|_| Vec::with_capacity(num_points)),
+        ..Default::default()
+    };
+    let mut rgb = Vec::with_capacity(layout.rgb_offset.map_or(0, |_| num_points));
+
+    for record in records.chunks_exact(record_length) {
+        let read_i32 = |offset: usize| {
+            i32::from_le_bytes([
+                record[offset],
+                record[offset + 1],
+                record[offset + 2],
+                record[offset + 3],
+            ]) as f64
+        };
+        let read_u16 = |offset: usize| u16::from_le_bytes([record[offset], record[offset + 1]]);
+
+        let raw_position = vec3(read_i32(0), read_i32(4), read_i32(8));
+        points.add_position(&(raw_position.component_mul(&header.scale) + header.offset - mins));
+
+        let (return_number, number_of_returns, classification, point_source_id) =
+            if layout.is_extended {
+                (record[14] & 0x0f, record[14] >> 4, record[16], read_u16(20))
+            } else {
+                (
+                    record[14] & 0x07,
+                    (record[14] >> 3) & 0x07,
+                    record[15] & 0x1f,
+                    read_u16(18),
+                )
+            };
+
+        if let Some(intensity) = &mut points.intensity {
+            intensity.push(read_u16(12) as f32);
+        }
+        if let Some(classifications) = &mut points.classification {
+            classifications.push(classification);
+        }
+        if let Some(return_numbers) = &mut points.return_number {
+            return_numbers.push(return_number);
+        }
+        if let Some(numbers_of_returns) = &mut points.number_of_returns {
+            numbers_of_returns.push(number_of_returns);
+        }
+        if let Some(point_source_ids) = &mut points.point_source_id {
+            point_source_ids.push(point_source_id);
+        }
+        if let (Some(offset), Some(gps_time)) = (layout.gps_time_offset, &mut points.gps_time) {
+            gps_time.push(f64::from_le_bytes(
+                record[offset..offset + 8].try_into().unwrap(),
+            ));
+        }
+        if let (Some(offset), Some(near_infrared)) =
+            (layout.near_infrared_offset, &mut points.near_infrared)
+        {
+            near_infrared.push(read_u16(offset));
+        }
+        if let Some(rgb_offset) = layout.rgb_offset {
+            rgb.push([
+                read_u16(rgb_offset),
+                read_u16(rgb_offset + 2),
+                read_u16(rgb_offset + 4),
+            ]);
+        }
+    }
+
+    if layout.rgb_offset.is_some() {
+        points.color = Some(create_color_buffer(&rgb));
+    }
+
+    Ok(points)
+}
+
+/// Decodes the points of an uncompressed LAS file into the same buffers as
+/// `decode_ept_binary`, along with GPS time and near infrared where the point format has them.
+/// Positions are `value * scale + offset - mins`, where the minimum of the header bounding
+/// box is a natural choice for `mins`
+pub fn decode_las(buffer: &[u8], mins: &DVec3) -> Result<DecodedPoints, String> {
+    let header = parse_header(buffer)?;
+    if header.is_compressed {
+        return Err("LAS file has compressed points, use decode_laz instead".to_string());
+    }
+
+    let records_start = header.offset_to_point_data as usize;
+    let records = usize::try_from(header.number_of_points)
+        .ok()
+        .and_then(|number_of_points| {
+            number_of_points.checked_mul(header.point_data_record_length as usize)
+        })
+        .and_then(|records_length| records_start.checked_add(records_length))
+        .and_then(|records_end| buffer.get(records_start..records_end))
+        .ok_or_else(|| {
+            format!(
+                "LAS point data is truncated: expected {} points of {} bytes at offset {}, but length is {}",
+                header.number_of_points,
+                header.point_data_record_length,
+                records_start,
+                buffer.len()
+            )
+        })?;
+
+    decode_point_records(records, &header, mins)
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::{decode_las, parse_evlrs, parse_header, parse_vlrs};
+
+    const LAS_1_2_FORMAT_3: &[u8] = include_bytes!("../../tests/fixtures/las12_format3.las");
+    const LAS_1_3_FORMAT_0: &[u8] = include_bytes!("../../tests/fixtures/las13_format0.las");
+    const LAS_1_4_FORMAT_8: &[u8] = include_bytes!("../../tests/fixtures/las14_format8.las");
+
+    #[test]
+    fn las_1_2_header_and_vlrs_are_parsed() {
+        let header = parse_header(LAS_1_2_FORMAT_3).unwrap();
+
+        assert_eq!(header.version, (1, 2));
+        assert_eq!(header.point_data_format, 3);
+        assert_eq!(header.number_of_points, 3);
+        assert_eq!(header.bounding_box.min, vec3(499999.5, 5999999.0, 10.0));
+        assert_eq!(header.bounding_box.max, vec3(500001.0, 6000002.0, 15.0));
+        assert_eq!(header.start_of_first_evlr, None);
+
+        let vlrs = parse_vlrs(LAS_1_2_FORMAT_3, &header).unwrap();
+        assert_eq!(vlrs.len(), 1);
+        assert_eq!(vlrs[0].user_id, "LASF_Projection");
+        assert_eq!(vlrs[0].record_id, 34735);
+        assert_eq!(vlrs[0].data.len(), 8);
+    }
+
+    #[test]
+    fn format_3_points_have_gps_time_and_colors() {
+        let header = parse_header(LAS_1_2_FORMAT_3).unwrap();
+
+        let points = decode_las(LAS_1_2_FORMAT_3, &header.bounding_box.min).unwrap();
+
+        assert_eq!(
+            points.position,
+            vec![1.5, 3.0, 0.0, 0.0, 1.0, 5.0, 0.5, 0.0, 2.0]
+        );
+        assert_eq!(points.intensity, Some(vec![1200.0, 30.0, 0.0]));
+        assert_eq!(points.classification, Some(vec![2, 5, 1]));
+        assert_eq!(points.return_number, Some(vec![1, 1, 2]));
+        assert_eq!(points.number_of_returns, Some(vec![1, 2, 2]));
+        assert_eq!(points.point_source_id, Some(vec![7, 7, 8]));
+        assert_eq!(points.gps_time, Some(vec![1000.5, 1001.25, 1002.0]));
+        assert_eq!(
+            points.color,
+            Some(vec![255, 0, 128, 0, 1, 2, 4, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(points.near_infrared, None);
+    }
+
+    #[test]
+    fn format_0_points_have_no_optional_attributes() {
+        let points = decode_las(LAS_1_3_FORMAT_0, &vec3(0.0, 0.0, 0.0)).unwrap();
+
+        assert_eq!(points.position, vec![1.0, 2.0, 3.0, 2.0, 4.0, 6.0]);
+        // The withheld flag shares the byte with the classification
+        assert_eq!(points.classification, Some(vec![3, 4]));
+        assert_eq!(points.gps_time, None);
+        assert_eq!(points.color, None);
+    }
+
+    #[test]
+    fn las_1_4_format_8_points_and_evlrs_are_decoded() {
+        let header = parse_header(LAS_1_4_FORMAT_8).unwrap();
+        assert_eq!(header.version, (1, 4));
+        assert_eq!(header.number_of_points, 2);
+
+        let evlrs = parse_evlrs(LAS_1_4_FORMAT_8, &header).unwrap();
+        assert_eq!(evlrs.len(), 1);
+        assert_eq!(evlrs[0].user_id, "fixture");
+        assert_eq!(evlrs[0].record_id, 42);
+        assert_eq!(evlrs[0].data, b"hello");
+
+        let points = decode_las(LAS_1_4_FORMAT_8, &header.bounding_box.min).unwrap();
+
+        assert_eq!(points.num_points(), 2);
+        assert!((points.mean() - vec3(0.5, 1.0, 1.5)).magnitude() < 1e-6);
+        assert_eq!(points.intensity, Some(vec![500.0, 65535.0]));
+        assert_eq!(points.classification, Some(vec![40, 2]));
+        assert_eq!(points.return_number, Some(vec![3, 1]));
+        assert_eq!(points.number_of_returns, Some(vec![5, 1]));
+        assert_eq!(points.point_source_id, Some(vec![9, 9]));
+        assert_eq!(points.gps_time, Some(vec![5.0e8, 5.0e8 + 1.0]));
+        assert_eq!(points.color, Some(vec![10, 20, 30, 0, 40, 50, 60, 0]));
+        assert_eq!(points.near_infrared, Some(vec![60000, 123]));
+    }
+
+    #[test]
+    fn truncated_and_compressed_files_are_rejected() {
+        let mins = vec3(0.0, 0.0, 0.0);
+        assert!(decode_las(&LAS_1_2_FORMAT_3[..300], &mins).is_err());
+        assert!(decode_las(&LAS_1_2_FORMAT_3[..100], &mins).is_err());
+
+        let mut compressed = LAS_1_2_FORMAT_3.to_vec();
+        compressed[104] |= 0x80;
+        assert!(decode_las(&compressed, &mins).is_err());
+    }
+
+    #[test]
+    fn overflowing_counts_and_offsets_are_rejected() {
+        let header = parse_header(LAS_1_4_FORMAT_8).unwrap();
+        let evlr_start = header.start_of_first_evlr.unwrap() as usize;
+
+        let mut huge_point_count = LAS_1_4_FORMAT_8.to_vec();
+        huge_point_count[247..255].copy_from_slice(&u64::MAX.to_le_bytes());
+        let error = decode_las(&huge_point_count, &vec3(0.0, 0.0, 0.0)).unwrap_err();
+        assert!(error.contains("truncated"));
+
+        let mut huge_evlr_offset = LAS_1_4_FORMAT_8.to_vec();
+        huge_evlr_offset[235..243].copy_from_slice(&(u64::MAX - 10).to_le_bytes());
+        let header = parse_header(&huge_evlr_offset).unwrap();
+        assert!(parse_evlrs(&huge_evlr_offset, &header).is_err());
+
+        let mut huge_evlr_length = LAS_1_4_FORMAT_8.to_vec();
+        huge_evlr_length[evlr_start + 20..evlr_start + 28].copy_from_slice(&u64::MAX.to_le_bytes());
+        let header = parse_header(&huge_evlr_length).unwrap();
+        assert!(parse_evlrs(&huge_evlr_length, &header).is_err());
+    }
+}
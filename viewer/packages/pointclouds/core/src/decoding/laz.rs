@@ -0,0 +1,194 @@
+use std::io::Cursor;
+
+use laz::{LasZipDecompressor, LazVlr};
+use nalgebra_glm::DVec3;
+
+use crate::decoding::las::{decode_point_records, parse_header, parse_vlrs, LasHeader};
+use crate::decoding::DecodedPoints;
+
+/// LAZ compresses runs of identical points to about 700 points per byte, so counts beyond this
+/// cannot be backed by the compressed data
+const MAX_POINTS_PER_COMPRESSED_BYTE: u64 = 4096;
+
+/// Number of points decompressed at a time, so that memory only grows with decoded points
+const DECOMPRESSION_BLOCK_SIZE: usize = 65536;
+
+/// Finds and parses the laszip variable length record, which describes how points are compressed
+pub(super) fn find_laz_vlr(buffer: &[u8], header: &LasHeader) -> Result<LazVlr, String> {
+    let laszip_vlr = parse_vlrs(buffer, header)?
+        .into_iter()
+        .find(|vlr| vlr.user_id == LazVlr::USER_ID && vlr.record_id == LazVlr::RECORD_ID)
+        .ok_or_else(|| "LAZ file is missing the laszip variable length record".to_string())?;
+
+    let laz_vlr = LazVlr::from_buffer(laszip_vlr.data)
+        .map_err(|e| format!("Invalid laszip variable length record: {}", e))?;
+
+    if laz_vlr.items_size() != header.point_data_record_length as u64 {
+        return Err(format!(
+            "LAZ items have {} bytes, but the LAS header specifies {} bytes per point",
+            laz_vlr.items_size(),
+            header.point_data_record_length
+        ));
+    }
+
+    Ok(laz_vlr)
+}
+
+/// Decompresses `number_of_points` records from `source`, which must be positioned at the
+/// chunk table offset preceding the compressed points
+pub(super) fn decompress_records(
+    source: Cursor<&[u8]>,
+    laz_vlr: LazVlr,
+    number_of_points: u64,
+) -> Result<Vec<u8>, String> {
+    let compressed_length = (source.get_ref().len() as u64).saturating_sub(source.position());
+    if number_of_points > compressed_length.saturating_mul(MAX_POINTS_PER_COMPRESSED_BYTE) {
+        return Err(format!(
+            "LAZ point count {} is too large for {} bytes of compressed data",
+            number_of_points, compressed_length
+        ));
+    }
+
+    let record_length = laz_vlr.items_size() as usize;
+    let records_length = usize::try_from(number_of_points)
+        .ok()
+        .and_then(|number_of_points| number_of_points.checked_mul(record_length))
+        .ok_or_else(|| format!("LAZ point count {} is too large", number_of_points))?;
+
+    let mut decompressor = LasZipDecompressor::new(source, laz_vlr)
+        .map_err(|e| format!("Failed to initialize LAZ decompression: {}", e))?;
+
+    let mut records = vec![];
+    while records.len() < records_length {
+        let start = records.len();
+        records.resize(
+            records_length.min(start + DECOMPRESSION_BLOCK_SIZE * record_length),
+            0,
+        );
+        decompressor
+            .decompress_many(&mut records[start..])
+            .map_err(|e| format!("Failed to decompress LAZ points: {}", e))?;
+    }
+
+    Ok(records)
+}
+
+/// Decodes a LAZ-compressed EPT tile into the same buffers as `decode_ept_binary`.
+/// Positions are `value * scale + offset - mins`, using the scale and offset in the LAS header
+pub fn decode_laz(buffer: &[u8], mins: &DVec3) -> Result<DecodedPoints, String> {
+    let header = parse_header(buffer)?;
+    let laz_vlr = find_laz_vlr(buffer, &header)?;
+
+    let mut source = Cursor::new(buffer);
+    source.set_position(header.offset_to_point_data as u64);
+    let records = decompress_records(source, laz_vlr, header.number_of_points)?;
+
+    decode_point_records(&records, &header, mins)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use laz::{LasZipCompressor, LazItemRecordBuilder, LazVlr};
+    use nalgebra_glm::vec3;
+
+    use super::decode_laz;
+    use crate::decoding::test_utils::{
+        create_laszip_vlr, create_laz_header_and_vlrs, TestLasHeader,
+    };
+
+    const HEADER_SIZE: usize = 227;
+
+    /// Point format 3 record: xyz, intensity, return bits, classification, scan angle, user data,
+    /// point source id, gps time and rgb
+    fn create_record(
+        xyz: [i32; 3],
+        intensity: u16,
+        returns: u8,
+        class: u8,
+        rgb: [u16; 3],
+    ) -> Vec<u8> {
+        let mut record = vec![];
+        xyz.iter().for_each(|v| record.extend(v.to_le_bytes()));
+        record.extend(intensity.to_le_bytes());
+        record.push(returns);
+        record.push(class);
+        record.extend([0, 0]);
+        record.extend(7u16.to_le_bytes());
+        record.extend(0.0f64.to_le_bytes());
+        rgb.iter().for_each(|c| record.extend(c.to_le_bytes()));
+        record
+    }
+
+    fn create_laz_file(records: &[Vec<u8>]) -> Vec<u8> {
+        let vlr = LazVlr::from_laz_items(
+            LazItemRecordBuilder::default_for_point_format_id(3, 0).unwrap(),
+        );
+        let header = TestLasHeader {
+            minor_version: 2,
+            point_format: 3,
+            record_length: records[0].len() as u16,
+            number_of_points: records.len() as u32,
+            scale: [0.01, 0.01, 0.1],
+            offset: [1000.0, 2000.0, 0.0],
+        };
+        let file = create_laz_header_and_vlrs(&header, &[create_laszip_vlr(&vlr)]);
+        let offset_to_point_data = file.len();
+
+        let mut output = Cursor::new(file);
+        output.set_position(offset_to_point_data as u64);
+        let mut compressor = LasZipCompressor::new(output, vlr).unwrap();
+        compressor.compress_many(&records.concat()).unwrap();
+        compressor.done().unwrap();
+        compressor.into_inner().into_inner()
+    }
+
+    #[test]
+    fn laz_points_are_decoded_like_binary_tiles() {
+        let file = create_laz_file(&[
+            create_record([150, -50, 120], 300, 0b001_010, 2, [512, 0, 65535]),
+            create_record([-250, 50, 140], 7, 0b010_010, 6, [256, 1024, 0]),
+        ]);
+
+        let points = decode_laz(&file, &vec3(1000.0, 2000.0, 10.0)).unwrap();
+
+        assert_eq!(points.num_points(), 2);
+        assert_eq!(points.position, vec![1.5, -0.5, 2.0, -2.5, 0.5, 4.0]);
+        assert_eq!(points.intensity, Some(vec![300.0, 7.0]));
+        assert_eq!(points.classification, Some(vec![2, 6]));
+        assert_eq!(points.return_number, Some(vec![2, 2]));
+        assert_eq!(points.number_of_returns, Some(vec![1, 2]));
+        assert_eq!(points.point_source_id, Some(vec![7, 7]));
+        assert_eq!(points.color, Some(vec![2, 0, 255, 0, 1, 4, 0, 0]));
+    }
+
+    #[test]
+    fn point_counts_beyond_the_compressed_data_are_rejected() {
+        let mut file = create_laz_file(&[create_record([0, 0, 0], 0, 0, 0, [0, 0, 0])]);
+
+        file[107..111].copy_from_slice(&u32::MAX.to_le_bytes());
+        let error = decode_laz(&file, &vec3(0.0, 0.0, 0.0)).unwrap_err();
+        assert!(error.contains("too large"));
+
+        file[107..111].copy_from_slice(&100_000u32.to_le_bytes());
+        assert!(decode_laz(&file, &vec3(0.0, 0.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn record_length_must_match_laz_items() {
+        let mut file = create_laz_file(&[create_record([0, 0, 0], 0, 0, 0, [0, 0, 0])]);
+        file[105..107].copy_from_slice(&28u16.to_le_bytes());
+
+        assert!(decode_laz(&file, &vec3(0.0, 0.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn files_without_laszip_vlr_are_rejected() {
+        let mut file = create_laz_file(&[create_record([0, 0, 0], 0, 0, 0, [0, 0, 0])]);
+        file[HEADER_SIZE + 2] = b'x';
+
+        assert!(decode_laz(&file, &vec3(0.0, 0.0, 0.0)).is_err());
+        assert!(decode_laz(b"not a laz file", &vec3(0.0, 0.0, 0.0)).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+mod copc;
+mod ept_binary;
+mod ept_zstandard;
+mod las;
+mod laz;
+mod ply;
+#[cfg(test)]
+mod test_utils;
+
+pub use copc::{CopcHierarchyEntry, CopcInfo, CopcKey, CopcReader};
+pub use ept_binary::{decode_ept_binary, AttributeSchema, AttributeType, EptLayout};
+pub use ept_zstandard::decode_ept_zstandard;
+pub use las::{
+    decode_las, parse_evlrs as parse_las_evlrs, parse_header as parse_las_header,
+    parse_vlrs as parse_las_vlrs, LasHeader, Vlr,
+};
+pub use laz::decode_laz;
+pub use ply::{decode_ply, DecodedPly};
+
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+
+/// Point attributes decoded from a tile, laid out like the buffers of `ParsedEptData`
+/// in the TypeScript workers. Attributes missing from the source are `None`
+#[derive(Debug, Default)]
+pub struct DecodedPoints {
+    /// xyz for each point
+    pub position: Vec<f32>,
+    /// rgba for each point, with alpha left at 0
+    pub color: Option<Vec<u8>>,
+    pub intensity: Option<Vec<f32>>,
+    pub classification: Option<Vec<u8>>,
+    pub return_number: Option<Vec<u8>>,
+    pub number_of_returns: Option<Vec<u8>>,
+    pub point_source_id: Option<Vec<u16>>,
+    pub gps_time: Option<Vec<f64>>,
+    pub near_infrared: Option<Vec<u16>>,
+    /// xyz for each point
+    pub normal: Option<Vec<f32>>,
+    /// Attributes without a dedicated buffer, like the extra vertex properties of PLY files
+    pub scalar_fields: Vec<ScalarField>,
+    /// Tight box around the decoded positions
+    pub tight_bounding_box: BoundingBox,
+    position_sum: DVec3,
+}
+
+/// One value for each decoded point, see `DecodedPoints::scalar_fields`
+#[derive(Debug, Default)]
+pub struct ScalarField {
+    pub name: String,
+    pub values: Vec<f32>,
+}
+
+impl DecodedPoints {
+    pub fn num_points(&self) -> usize {
+        self.position.len() / 3
+    }
+
+    /// Mean of the decoded positions
+    pub fn mean(&self) -> DVec3 {
+        self.position_sum / self.num_points().max(1) as f64
+    }
+
+    fn add_position(&mut self, position: &DVec3) {
+        self.position
+            .extend([position.x as f32, position.y as f32, position.z as f32]);
+        self.tight_bounding_box.add_point(position);
+        self.position_sum += position;
+    }
+}
+
+/// Reads `N` bytes starting at `offset`, failing instead of panicking on truncated input
+fn read_bytes<const N: usize>(buffer: &[u8], offset: usize) -> Result<[u8; N], String> {
+    offset
+        .checked_add(N)
+        .and_then(|end| buffer.get(offset..end))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            format!(
+                "Unexpected end of buffer: needed {} bytes at offset {}, but length is {}",
+                N,
+                offset,
+                buffer.len()
+            )
+        })
+}
+
+/// Converts rgb colors to the rgba layout of `DecodedPoints::color`. Colors are scaled down
+/// to 8 bits if any component is larger than 255, like for EPT binary tiles
+fn create_color_buffer(rgb: &[[u16; 3]]) -> Vec<u8> {
+    let two_byte_color = rgb.iter().flatten().any(|&component| component > 255);
+    let shift = if two_byte_color { 8 } else { 0 };
+
+    rgb.iter()
+        .flat_map(|color| {
+            [
+                (color[0] >> shift) as u8,
+                (color[1] >> shift) as u8,
+                (color[2] >> shift) as u8,
+                0,
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,545 @@
+use std::str::SplitAsciiWhitespace;
+
+use nalgebra_glm::{vec3, DVec3};
+
+use crate::decoding::{create_color_buffer, DecodedPoints, ScalarField};
+
+const END_HEADER: &[u8] = b"end_header";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PlyType {
+    fn try_parse(name: &str) -> Result<Self, String> {
+        match name {
+            "char" | "int8" => Ok(PlyType::Int8),
+            "uchar" | "uint8" => Ok(PlyType::UInt8),
+            "short" | "int16" => Ok(PlyType::Int16),
+            "ushort" | "uint16" => Ok(PlyType::UInt16),
+            "int" | "int32" => Ok(PlyType::Int32),
+            "uint" | "uint32" => Ok(PlyType::UInt32),
+            "float" | "float32" => Ok(PlyType::Float32),
+            "double" | "float64" => Ok(PlyType::Float64),
+            _ => Err(format!("Unknown PLY property type {}", name)),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlyType::Int8 | PlyType::UInt8 => 1,
+            PlyType::Int16 | PlyType::UInt16 => 2,
+            PlyType::Int32 | PlyType::UInt32 | PlyType::Float32 => 4,
+            PlyType::Float64 => 8,
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        matches!(self, PlyType::Float32 | PlyType::Float64)
+    }
+}
+
+#[derive(Debug)]
+struct PlyProperty {
+    name: String,
+    data_type: PlyType,
+    /// Type of the item count, for list properties
+    list_count_type: Option<PlyType>,
+}
+
+#[derive(Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+impl PlyElement {
+    /// Smallest number of bytes a row of this element can take up in the body
+    fn get_min_row_size(&self, format: PlyFormat) -> usize {
+        self.properties
+            .iter()
+            .map(|property| match format {
+                // A value and a separator
+                PlyFormat::Ascii => 2,
+                _ => property
+                    .list_count_type
+                    .unwrap_or(property.data_type)
+                    .size(),
+            })
+            .sum()
+    }
+}
+
+#[derive(Debug)]
+struct PlyHeader {
+    format: PlyFormat,
+    elements: Vec<PlyElement>,
+    /// Offset of the first byte after the header
+    body_offset: usize,
+}
+
+fn parse_header(buffer: &[u8]) -> Result<PlyHeader, String> {
+    if !buffer.starts_with(b"ply") {
+        return Err("Not a PLY file, the magic number is missing".to_string());
+    }
+
+    let end_header = buffer
+        .windows(END_HEADER.len())
+        .position(|window| window == END_HEADER)
+        .ok_or_else(|| "PLY header is missing end_header".to_string())?;
+    let body_offset = buffer[end_header..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map(|newline| end_header + newline + 1)
+        .ok_or_else(|| "PLY header is not terminated by a newline".to_string())?;
+
+    let header_text = std::str::from_utf8(&buffer[..end_header])
+        .map_err(|e| format!("PLY header is not valid text: {}", e))?;
+
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = vec![];
+
+    for line in header_text.lines().skip(1) {
+        let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
+        match tokens[..] {
+            [] | ["comment", ..] | ["obj_info", ..] => {}
+            ["format", name, _version] => {
+                format = Some(match name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(format!("Unknown PLY format {}", name)),
+                });
+            }
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .map_err(|_| format!("Invalid PLY element count {}", count))?,
+                properties: vec![],
+            }),
+            ["property", "list", count_type, data_type, name] => elements
+                .last_mut()
+                .ok_or_else(|| format!("PLY property {} is not part of an element", name))?
+                .properties
+                .push(PlyProperty {
+                    name: name.to_string(),
+                    data_type: PlyType::try_parse(data_type)?,
+                    list_count_type: Some(PlyType::try_parse(count_type)?),
+                }),
+            ["property", data_type, name] => elements
+                .last_mut()
+                .ok_or_else(|| format!("PLY property {} is not part of an element", name))?
+                .properties
+                .push(PlyProperty {
+                    name: name.to_string(),
+                    data_type: PlyType::try_parse(data_type)?,
+                    list_count_type: None,
+                }),
+            _ => return Err(format!("Unexpected line in PLY header: {}", line)),
+        }
+    }
+
+    Ok(PlyHeader {
+        format: format.ok_or_else(|| "PLY header is missing the format".to_string())?,
+        elements,
+        body_offset,
+    })
+}
+
+/// Reads property values from the body of a PLY file, in the order they are stored
+enum ValueReader<'a> {
+    Ascii(SplitAsciiWhitespace<'a>),
+    Binary {
+        body: &'a [u8],
+        offset: usize,
+        big_endian: bool,
+    },
+}
+
+impl<'a> ValueReader<'a> {
+    fn try_new(buffer: &'a [u8], header: &PlyHeader) -> Result<Self, String> {
+        let body = &buffer[header.body_offset..];
+        match header.format {
+            PlyFormat::Ascii => std::str::from_utf8(body)
+                .map(|text| ValueReader::Ascii(text.split_ascii_whitespace()))
+                .map_err(|e| format!("PLY body is not valid text: {}", e)),
+            PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => Ok(ValueReader::Binary {
+                body,
+                offset: 0,
+                big_endian: header.format == PlyFormat::BinaryBigEndian,
+            }),
+        }
+    }
+
+    fn read(&mut self, data_type: PlyType) -> Result<f64, String> {
+        match self {
+            ValueReader::Ascii(tokens) => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| "Unexpected end of PLY data".to_string())?;
+                token
+                    .parse()
+                    .map_err(|_| format!("Invalid PLY value {}", token))
+            }
+            ValueReader::Binary {
+                body,
+                offset,
+                big_endian,
+            } => {
+                let size = data_type.size();
+                let bytes = body.get(*offset..*offset + size).ok_or_else(|| {
+                    format!(
+                        "Unexpected end of PLY data: needed {} bytes at offset {}",
+                        size, offset
+                    )
+                })?;
+                *offset += size;
+
+                // Reverse big-endian values, so both cases can be read as little-endian
+                let mut value = [0; 8];
+                value[..size].copy_from_slice(bytes);
+                if *big_endian {
+                    value[..size].reverse();
+                }
+
+                Ok(match data_type {
+                    PlyType::Int8 => value[0] as i8 as f64,
+                    PlyType::UInt8 => value[0] as f64,
+                    PlyType::Int16 => i16::from_le_bytes([value[0], value[1]]) as f64,
+                    PlyType::UInt16 => u16::from_le_bytes([value[0], value[1]]) as f64,
+                    PlyType::Int32 => {
+                        i32::from_le_bytes([value[0], value[1], value[2], value[3]]) as f64
+                    }
+                    PlyType::UInt32 => {
+                        u32::from_le_bytes([value[0], value[1], value[2], value[3]]) as f64
+                    }
+                    PlyType::Float32 => {
+                        f32::from_le_bytes([value[0], value[1], value[2], value[3]]) as f64
+                    }
+                    PlyType::Float64 => f64::from_le_bytes(value),
+                })
+            }
+        }
+    }
+
+    /// Reads all properties of one element, returning the first item of list properties
+    fn read_row(&mut self, element: &PlyElement, row: &mut Vec<f64>) -> Result<(), String> {
+        row.clear();
+        for property in &element.properties {
+            match property.list_count_type {
+                Some(count_type) => {
+                    let count = self.read(count_type)? as usize;
+                    let mut first = f64::NAN;
+                    for i in 0..count {
+                        let value = self.read(property.data_type)?;
+                        if i == 0 {
+                            first = value;
+                        }
+                    }
+                    row.push(first);
+                }
+                None => row.push(self.read(property.data_type)?),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Points decoded from a PLY file, whose positions are relative to `mins`
+#[derive(Debug)]
+pub struct DecodedPly {
+    pub points: DecodedPoints,
+    /// Minimum corner of the vertex positions
+    pub mins: DVec3,
+}
+
+/// Decodes the vertices of an ASCII or binary PLY file into the same buffers as
+/// `decode_ept_binary`. x/y/z, red/green/blue, intensity and nx/ny/nz are taken from the
+/// vertex properties with those names, and all other non-list vertex properties become
+/// scalar fields. Float colors are assumed to be in the range 0 to 1
+pub fn decode_ply(buffer: &[u8]) -> Result<DecodedPly, String> {
+    let header = parse_header(buffer)?;
+    let mut reader = ValueReader::try_new(buffer, &header)?;
+    let mut row = vec![];
+
+    let vertex_index = header
+        .elements
+        .iter()
+        .position(|element| element.name == "vertex")
+        .ok_or_else(|| "PLY file has no vertex element".to_string())?;
+
+    // Skip the elements stored before the vertices
+    for element in &header.elements[..vertex_index] {
+        if element.properties.is_empty() {
+            continue;
+        }
+        for _ in 0..element.count {
+            reader.read_row(element, &mut row)?;
+        }
+    }
+
+    let vertex = &header.elements[vertex_index];
+    let find = |name: &str| {
+        vertex
+            .properties
+            .iter()
+            .position(|property| property.list_count_type.is_none() && property.name == name)
+    };
+    let find_all = |names: [&str; 3]| match names.map(find) {
+        [Some(a), Some(b), Some(c)] => Some([a, b, c]),
+        _ => None,
+    };
+
+    let xyz = find_all(["x", "y", "z"])
+        .ok_or_else(|| "PLY vertex element is missing x, y or z".to_string())?;
+    let rgb = find_all(["red", "green", "blue"]);
+    let normal = find_all(["nx", "ny", "nz"]);
+    let intensity = find("intensity");
+
+    let known_indices: Vec<usize> = [Some(xyz), rgb, normal]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .chain(intensity)
+        .collect();
+    let scalar_field_indices: Vec<usize> = (0..vertex.properties.len())
+        .filter(|i| !known_indices.contains(i) && vertex.properties[*i].list_count_type.is_none())
+        .collect();
+
+    let color_scale = match rgb {
+        Some([red, _, _]) if vertex.properties[red].data_type.is_float() => 255.0,
+        _ => 1.0,
+    };
+
+    // The count in the header is not trusted for allocation, as the body may be much shorter
+    let num_points = vertex.count;
+    let body_length = buffer.len() - header.body_offset;
+    let capacity = num_points.min(body_length / vertex.get_min_row_size(header.format));
+    let mut positions = Vec::with_capacity(capacity);
+    let mut colors = Vec::with_capacity(rgb.map_or(0, |_| capacity));
+    let mut points = DecodedPoints {
+        position: Vec::with_capacity(3 * capacity),
+        intensity: intensity.map(|_| Vec::with_capacity(capacity)),
+        normal: normal.map(|_| Vec::with_capacity(3 * capacity)),
+        scalar_fields: scalar_field_indices
+            .iter()
+            .map(|&i| ScalarField {
+                name: vertex.properties[i].name.clone(),
+                values: Vec::with_capacity(capacity),
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    for _ in 0..num_points {
+        reader.read_row(vertex, &mut row)?;
+
+        positions.push(vec3(row[xyz[0]], row[xyz[1]], row[xyz[2]]));
+        if let Some(rgb) = rgb {
+            colors.push(rgb.map(|i| (row[i] * color_scale).clamp(0.0, 65535.0) as u16));
+        }
+        if let (Some(i), Some(intensity)) = (intensity, &mut points.intensity) {
+            intensity.push(row[i] as f32);
+        }
+        if let (Some(indices), Some(normals)) = (normal, &mut points.normal) {
+            normals.extend(indices.map(|i| row[i] as f32));
+        }
+        for (field, &i) in points.scalar_fields.iter_mut().zip(&scalar_field_indices) {
+            field.values.push(row[i] as f32);
+        }
+    }
+
+    let mins = positions
+        .iter()
+        .copied()
+        .reduce(|a, b| a.inf(&b))
+        .unwrap_or_default();
+    positions
+        .iter()
+        .for_each(|position| points.add_position(&(position - mins)));
+
+    if rgb.is_some() {
+        points.color = Some(create_color_buffer(&colors));
+    }
+
+    Ok(DecodedPly { points, mins })
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::decode_ply;
+
+    const HEADER: &str = "element vertex 2
+property float x
+property float y
+property double z
+property uchar red
+property uchar green
+property uchar blue
+property float nx
+property float ny
+property float nz
+property ushort intensity
+property float confidence
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    fn create_binary(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian {
+            "binary_big_endian"
+        } else {
+            "binary_little_endian"
+        };
+        let mut buffer =
+            format!("ply\nformat {} 1.0\ncomment test\n{}", format, HEADER).into_bytes();
+        let mut push = |mut value: Vec<u8>| {
+            if big_endian {
+                value.reverse();
+            }
+            buffer.extend(value);
+        };
+
+        for (x, y, z, rgb, normal, intensity, confidence) in [
+            (
+                10.0f32,
+                20.0f32,
+                5.0f64,
+                [255u8, 0, 128],
+                [0.0f32, 0.0, 1.0],
+                300u16,
+                0.5f32,
+            ),
+            (12.0, 18.0, 6.0, [1, 2, 3], [1.0, 0.0, 0.0], 7, 0.25),
+        ] {
+            push(x.to_le_bytes().to_vec());
+            push(y.to_le_bytes().to_vec());
+            push(z.to_le_bytes().to_vec());
+            rgb.iter().for_each(|&c| push(vec![c]));
+            normal.iter().for_each(|n| push(n.to_le_bytes().to_vec()));
+            push(intensity.to_le_bytes().to_vec());
+            push(confidence.to_le_bytes().to_vec());
+        }
+        push(vec![3]);
+        [0i32, 1, 0]
+            .iter()
+            .for_each(|i| push(i.to_le_bytes().to_vec()));
+
+        buffer
+    }
+
+    fn assert_decoded_test_vertices(buffer: &[u8]) {
+        let ply = decode_ply(buffer).unwrap();
+        let points = ply.points;
+
+        assert_eq!(ply.mins, vec3(10.0, 18.0, 5.0));
+        assert_eq!(points.position, vec![0.0, 2.0, 0.0, 2.0, 0.0, 1.0]);
+        assert_eq!(points.color, Some(vec![255, 0, 128, 0, 1, 2, 3, 0]));
+        assert_eq!(points.intensity, Some(vec![300.0, 7.0]));
+        assert_eq!(points.normal, Some(vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0]));
+        assert_eq!(points.scalar_fields.len(), 1);
+        assert_eq!(points.scalar_fields[0].name, "confidence");
+        assert_eq!(points.scalar_fields[0].values, vec![0.5, 0.25]);
+        assert_eq!(points.classification, None);
+    }
+
+    #[test]
+    fn ascii_vertices_are_decoded() {
+        let buffer = format!(
+            "ply\r\nformat ascii 1.0\r\n{}10 20 5 255 0 128 0 0 1 300 0.5\n12 18 6 1 2 3 1 0 0 7 0.25\n3 0 1 0\n",
+            HEADER.replace('\n', "\r\n")
+        );
+
+        assert_decoded_test_vertices(buffer.as_bytes());
+    }
+
+    #[test]
+    fn binary_little_endian_vertices_are_decoded() {
+        assert_decoded_test_vertices(&create_binary(false));
+    }
+
+    #[test]
+    fn binary_big_endian_vertices_are_decoded() {
+        assert_decoded_test_vertices(&create_binary(true));
+    }
+
+    #[test]
+    fn elements_before_vertices_are_skipped() {
+        let buffer = "ply
+format ascii 1.0
+element camera 2
+property list uchar float position
+property float focal_length
+element vertex 1
+property float x
+property float y
+property float z
+property float red
+property float green
+property float blue
+end_header
+2 1 2 35
+0 50
+1 2 3 1.0 0.5 0
+";
+
+        let points = decode_ply(buffer.as_bytes()).unwrap().points;
+
+        assert_eq!(points.position, vec![0.0, 0.0, 0.0]);
+        assert_eq!(points.color, Some(vec![255, 127, 0, 0]));
+        assert_eq!(points.intensity, None);
+        assert!(points.scalar_fields.is_empty());
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        assert!(decode_ply(b"not a ply file").is_err());
+        assert!(
+            decode_ply(b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\n").is_err()
+        );
+        assert!(decode_ply(
+            b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\nend_header\n1 2\n"
+        )
+        .is_err());
+
+        let mut truncated = create_binary(false);
+        truncated.truncate(truncated.len() - 30);
+        assert!(decode_ply(&truncated).is_err());
+    }
+
+    #[test]
+    fn huge_vertex_counts_of_truncated_files_are_rejected() {
+        for format in ["ascii", "binary_little_endian"] {
+            for count in ["3000000000000000000", "1000000000"] {
+                let buffer = format!(
+                    "ply\nformat {} 1.0\nelement face {}\nelement vertex {}\n\
+                     property double x\nproperty double y\nproperty double z\nend_header\n\
+                     1 2 3 4 5 6",
+                    format, count, count
+                );
+
+                assert!(decode_ply(buffer.as_bytes()).is_err());
+            }
+        }
+    }
+}
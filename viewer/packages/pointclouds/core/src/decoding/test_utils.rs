@@ -0,0 +1,60 @@
+//! Builders for the LAS headers and variable length records used by the LAZ and COPC tests
+
+use laz::LazVlr;
+
+/// Header fields that the tests vary, the rest are left at zero
+pub struct TestLasHeader {
+    /// 2 for LAS 1.2, 4 for LAS 1.4
+    pub minor_version: u8,
+    pub point_format: u8,
+    pub record_length: u16,
+    pub number_of_points: u32,
+    pub scale: [f64; 3],
+    pub offset: [f64; 3],
+}
+
+pub fn create_vlr(user_id: &str, record_id: u16, data: &[u8]) -> Vec<u8> {
+    let mut vlr = vec![0, 0];
+    let mut padded_user_id = [0u8; 16];
+    padded_user_id[..user_id.len()].copy_from_slice(user_id.as_bytes());
+    vlr.extend(padded_user_id);
+    vlr.extend(record_id.to_le_bytes());
+    vlr.extend((data.len() as u16).to_le_bytes());
+    vlr.extend([0; 32]);
+    vlr.extend(data);
+    vlr
+}
+
+pub fn create_laszip_vlr(laz_vlr: &LazVlr) -> Vec<u8> {
+    let mut data = vec![];
+    laz_vlr.write_to(&mut data).unwrap();
+    create_vlr(LazVlr::USER_ID, LazVlr::RECORD_ID, &data)
+}
+
+/// Creates the header of a LAZ-compressed file followed by the VLRs, so that the point data
+/// starts at the end of the returned buffer
+pub fn create_laz_header_and_vlrs(header: &TestLasHeader, vlrs: &[Vec<u8>]) -> Vec<u8> {
+    let header_size: usize = if header.minor_version >= 4 { 375 } else { 227 };
+    let offset_to_point_data = header_size + vlrs.iter().map(Vec::len).sum::<usize>();
+
+    let mut file = vec![0; header_size];
+    file[0..4].copy_from_slice(b"LASF");
+    file[24] = 1;
+    file[25] = header.minor_version;
+    file[94..96].copy_from_slice(&(header_size as u16).to_le_bytes());
+    file[96..100].copy_from_slice(&(offset_to_point_data as u32).to_le_bytes());
+    file[100..104].copy_from_slice(&(vlrs.len() as u32).to_le_bytes());
+    file[104] = header.point_format | 0x80;
+    file[105..107].copy_from_slice(&header.record_length.to_le_bytes());
+    file[107..111].copy_from_slice(&header.number_of_points.to_le_bytes());
+    if header.minor_version >= 4 {
+        file[247..255].copy_from_slice(&(header.number_of_points as u64).to_le_bytes());
+    }
+    for i in 0..3 {
+        file[131 + 8 * i..139 + 8 * i].copy_from_slice(&header.scale[i].to_le_bytes());
+        file[155 + 8 * i..163 + 8 * i].copy_from_slice(&header.offset[i].to_le_bytes());
+    }
+
+    vlrs.iter().for_each(|vlr| file.extend(vlr));
+    file
+}
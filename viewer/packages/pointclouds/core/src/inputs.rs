@@ -0,0 +1,707 @@
+use nalgebra_glm::{inverse, scaling, vec3, DMat4, DVec3};
+use std::vec::Vec;
+
+use crate::decoding;
+use crate::linalg::BoundingBox;
+use crate::object_assignment::OverlapResolution;
+use crate::shapes;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct InputCylinder {
+    center_a: [f64; 3],
+    center_b: [f64; 3],
+    radius: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputCone {
+    center_a: [f64; 3],
+    center_b: [f64; 3],
+    radius_a: f64,
+    radius_b: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputOrientedBox {
+    inv_instance_matrix: [f64; 16],
+}
+
+#[derive(Debug, Deserialize)]
+struct InputEllipsoid {
+    inv_instance_matrix: [f64; 16],
+}
+
+#[derive(Debug, Deserialize)]
+struct InputSphere {
+    center: [f64; 3],
+    radius: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputTorusSegment {
+    center: [f64; 3],
+    axis: [f64; 3],
+    reference_direction: [f64; 3],
+    major_radius: f64,
+    minor_radius: f64,
+    start_angle: f64,
+    end_angle: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputCapsule {
+    center_a: [f64; 3],
+    center_b: [f64; 3],
+    radius: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputPipeRun {
+    centers: Vec<[f64; 3]>,
+    radius: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputHalfSpace {
+    normal: [f64; 3],
+    offset: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputConvexPolyhedron {
+    half_spaces: Option<Vec<InputHalfSpace>>,
+    vertices: Option<Vec<[f64; 3]>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputPrism {
+    vertices: Vec<[f64; 3]>,
+    axis: [f64; 3],
+    min_height: f64,
+    max_height: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputTriangleMesh {
+    vertices: Vec<f64>,
+    indices: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InputCompositeOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl From<InputCompositeOperation> for shapes::CompositeOperation {
+    fn from(input: InputCompositeOperation) -> shapes::CompositeOperation {
+        match input {
+            InputCompositeOperation::Union => shapes::CompositeOperation::Union,
+            InputCompositeOperation::Intersection => shapes::CompositeOperation::Intersection,
+            InputCompositeOperation::Difference => shapes::CompositeOperation::Difference,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InputComposite {
+    operation: InputCompositeOperation,
+    children: Vec<InputShape>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct InputTolerance {
+    relative_factor: Option<f64>,
+    absolute_margin: Option<f64>,
+}
+
+impl From<InputTolerance> for shapes::Tolerance {
+    fn from(input: InputTolerance) -> shapes::Tolerance {
+        shapes::Tolerance {
+            relative_factor: input.relative_factor,
+            absolute_margin: input.absolute_margin,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InputShape {
+    /// Required for the shapes passed in, not needed for children of composite shapes
+    object_id: Option<u32>,
+    /// Overrides the tolerance of the call, or of the parent for children of composite shapes.
+    /// Children subtracted by a difference are not inflated unless they set this
+    tolerance: Option<InputTolerance>,
+    cylinder: Option<Box<InputCylinder>>,
+    oriented_box: Option<Box<InputOrientedBox>>,
+    sphere: Option<Box<InputSphere>>,
+    cone: Option<Box<InputCone>>,
+    torus_segment: Option<Box<InputTorusSegment>>,
+    capsule: Option<Box<InputCapsule>>,
+    pipe_run: Option<Box<InputPipeRun>>,
+    convex_polyhedron: Option<Box<InputConvexPolyhedron>>,
+    prism: Option<Box<InputPrism>>,
+    triangle_mesh: Option<Box<InputTriangleMesh>>,
+    composite: Option<Box<InputComposite>>,
+    ellipsoid: Option<Box<InputEllipsoid>>,
+}
+
+#[derive(Deserialize)]
+pub struct InputBoundingBox {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl From<InputBoundingBox> for BoundingBox {
+    fn from(input: InputBoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: vec3(input.min[0], input.min[1], input.min[2]),
+            max: vec3(input.max[0], input.max[1], input.max[2]),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum InputAttributeType {
+    Signed,
+    Unsigned,
+    Float,
+}
+
+#[derive(Debug, Deserialize)]
+struct InputAttributeSchema {
+    name: String,
+    size: usize,
+    #[serde(rename = "type")]
+    attribute_type: InputAttributeType,
+}
+
+impl From<InputAttributeSchema> for decoding::AttributeSchema {
+    fn from(input: InputAttributeSchema) -> decoding::AttributeSchema {
+        decoding::AttributeSchema {
+            name: input.name,
+            size: input.size,
+            attribute_type: match input.attribute_type {
+                InputAttributeType::Signed => decoding::AttributeType::Signed,
+                InputAttributeType::Unsigned => decoding::AttributeType::Unsigned,
+                InputAttributeType::Float => decoding::AttributeType::Float,
+            },
+        }
+    }
+}
+
+/// `EptInputData` from the TypeScript workers, without the buffer
+#[derive(Debug, Deserialize)]
+pub struct InputEptLayout {
+    schema: Vec<InputAttributeSchema>,
+    scale: [f64; 3],
+    offset: [f64; 3],
+    mins: [f64; 3],
+}
+
+impl From<InputEptLayout> for decoding::EptLayout {
+    fn from(input: InputEptLayout) -> decoding::EptLayout {
+        decoding::EptLayout {
+            schema: input.schema.into_iter().map(Into::into).collect(),
+            scale: input.scale.into(),
+            offset: input.offset.into(),
+            mins: input.mins.into(),
+        }
+    }
+}
+
+fn create_cylinder(
+    input: InputCylinder,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::Cylinder> {
+    let radius = tolerance.inflate(input.radius);
+    Box::new(shapes::Cylinder::new(
+        vec3(input.center_a[0], input.center_a[1], input.center_a[2]),
+        vec3(input.center_b[0], input.center_b[1], input.center_b[2]),
+        radius,
+        id,
+    ))
+}
+
+fn create_cone(input: InputCone, id: u32, tolerance: &shapes::Tolerance) -> Box<shapes::Cone> {
+    Box::new(shapes::Cone::new(
+        vec3(input.center_a[0], input.center_a[1], input.center_a[2]),
+        vec3(input.center_b[0], input.center_b[1], input.center_b[2]),
+        tolerance.inflate(input.radius_a),
+        tolerance.inflate(input.radius_b),
+        id,
+    ))
+}
+
+/// Inflates the half-size along each axis of the unit shape given by the matrix
+fn inflate_inv_instance_matrix(
+    inv_instance_matrix: &[f64; 16],
+    tolerance: &shapes::Tolerance,
+) -> DMat4 {
+    let matrix = DMat4::from_column_slice(inv_instance_matrix);
+    let instance_matrix = inverse(&matrix);
+    let inverse_factors = DVec3::from_fn(|axis, _| {
+        let extent = instance_matrix.fixed_view::<3, 1>(0, axis).magnitude();
+        1.0 / tolerance.get_scale_factor(extent)
+    });
+
+    scaling(&inverse_factors) * matrix
+}
+
+fn create_box(
+    input: InputOrientedBox,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::OrientedBox> {
+    let scaled_matrix = inflate_inv_instance_matrix(&input.inv_instance_matrix, tolerance);
+    Box::new(shapes::OrientedBox::new(scaled_matrix, id))
+}
+
+fn create_ellipsoid(
+    input: InputEllipsoid,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::Ellipsoid> {
+    let scaled_matrix = inflate_inv_instance_matrix(&input.inv_instance_matrix, tolerance);
+    Box::new(shapes::Ellipsoid::new(scaled_matrix, id))
+}
+
+fn create_sphere(
+    input: InputSphere,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::Sphere> {
+    Box::new(shapes::Sphere::new(
+        vec3(input.center[0], input.center[1], input.center[2]),
+        tolerance.inflate(input.radius),
+        id,
+    ))
+}
+
+fn create_torus_segment(
+    input: InputTorusSegment,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::TorusSegment> {
+    Box::new(shapes::TorusSegment::new(
+        vec3(input.center[0], input.center[1], input.center[2]),
+        vec3(input.axis[0], input.axis[1], input.axis[2]),
+        vec3(
+            input.reference_direction[0],
+            input.reference_direction[1],
+            input.reference_direction[2],
+        ),
+        input.major_radius,
+        tolerance.inflate(input.minor_radius),
+        input.start_angle,
+        input.end_angle,
+        id,
+    ))
+}
+
+fn create_capsule(
+    input: InputCapsule,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Box<shapes::Capsule> {
+    Box::new(shapes::Capsule::new(
+        vec3(input.center_a[0], input.center_a[1], input.center_a[2]),
+        vec3(input.center_b[0], input.center_b[1], input.center_b[2]),
+        tolerance.inflate(input.radius),
+        id,
+    ))
+}
+
+fn create_pipe_run(
+    input: InputPipeRun,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Result<Box<shapes::PipeRun>, String> {
+    if input.centers.len() < 2 {
+        return Err("Pipe run must have at least two centers".to_string());
+    }
+
+    let centers: Vec<_> = input
+        .centers
+        .iter()
+        .map(|center| vec3(center[0], center[1], center[2]))
+        .collect();
+
+    Ok(Box::new(shapes::PipeRun::new(
+        &centers,
+        tolerance.inflate(input.radius),
+        id,
+    )))
+}
+
+fn create_convex_polyhedron(
+    input: InputConvexPolyhedron,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Result<Box<shapes::ConvexPolyhedron>, String> {
+    let polyhedron = if let Some(input_half_spaces) = input.half_spaces {
+        let half_spaces: Vec<_> = input_half_spaces
+            .iter()
+            .map(|half_space| shapes::HalfSpace {
+                normal: vec3(
+                    half_space.normal[0],
+                    half_space.normal[1],
+                    half_space.normal[2],
+                ),
+                offset: half_space.offset,
+            })
+            .collect();
+        shapes::ConvexPolyhedron::from_half_spaces(&half_spaces, id)
+    } else if let Some(input_vertices) = input.vertices {
+        let vertices: Vec<_> = input_vertices
+            .iter()
+            .map(|vertex| vec3(vertex[0], vertex[1], vertex[2]))
+            .collect();
+        shapes::ConvexPolyhedron::from_vertices(&vertices, id)
+    } else {
+        Err("Convex polyhedron needs either half-spaces or vertices".to_string())
+    }?;
+
+    Ok(Box::new(polyhedron.inflated(tolerance)?))
+}
+
+fn create_prism(
+    input: InputPrism,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Result<Box<shapes::Prism>, String> {
+    if input.vertices.len() < 3 {
+        return Err("Prism polygon must have at least three vertices".to_string());
+    }
+
+    let vertices: Vec<_> = input
+        .vertices
+        .iter()
+        .map(|vertex| vec3(vertex[0], vertex[1], vertex[2]))
+        .collect();
+
+    Ok(Box::new(
+        shapes::Prism::new(
+            &vertices,
+            vec3(input.axis[0], input.axis[1], input.axis[2]),
+            input.min_height,
+            input.max_height,
+            id,
+        )
+        .inflated(tolerance),
+    ))
+}
+
+fn create_triangle_mesh(
+    input: InputTriangleMesh,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Result<Box<shapes::TriangleMesh>, String> {
+    if !input.vertices.len().is_multiple_of(3) || !input.indices.len().is_multiple_of(3) {
+        return Err("Triangle mesh buffer lengths must be multiples of three".to_string());
+    }
+
+    let num_vertices = input.vertices.len() / 3;
+    if input.indices.is_empty() || input.indices.iter().any(|&i| i as usize >= num_vertices) {
+        return Err("Triangle mesh has invalid vertex indices".to_string());
+    }
+
+    let vertices = input
+        .vertices
+        .chunks(3)
+        .map(|v| vec3(v[0], v[1], v[2]))
+        .collect();
+    let triangles = input
+        .indices
+        .chunks(3)
+        .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+        .collect();
+
+    Ok(Box::new(
+        shapes::TriangleMesh::new(vertices, triangles, id).inflated(tolerance),
+    ))
+}
+
+fn create_composite(
+    input: InputComposite,
+    id: u32,
+    tolerance: &shapes::Tolerance,
+) -> Result<Box<shapes::Composite>, String> {
+    if input.children.is_empty() {
+        return Err("Composite shape must have at least one child".to_string());
+    }
+
+    // Inflating a subtracted child would shrink the result, so the children removed by a
+    // difference keep their exact geometry unless they set a tolerance of their own
+    let is_difference = matches!(input.operation, InputCompositeOperation::Difference);
+    let exact = shapes::Tolerance {
+        relative_factor: None,
+        absolute_margin: None,
+    };
+    let children: Result<Vec<_>, _> = input
+        .children
+        .into_iter()
+        .enumerate()
+        .map(|(i, child)| {
+            let is_subtracted = is_difference && i > 0;
+            let child_tolerance = if is_subtracted { &exact } else { tolerance };
+            create_shape_with_id(child, 0, child_tolerance)
+        })
+        .collect();
+
+    Ok(Box::new(shapes::Composite::new(
+        input.operation.into(),
+        children?,
+        id,
+    )))
+}
+
+pub fn create_shape(
+    obj: InputShape,
+    default_tolerance: &shapes::Tolerance,
+) -> Result<Box<dyn shapes::Shape>, String> {
+    let object_id = obj
+        .object_id
+        .ok_or_else(|| "Shape is missing its object id".to_string())?;
+    create_shape_with_id(obj, object_id, default_tolerance)
+}
+
+/// Creates the shape with the given object id, which children of composite shapes do not need
+fn create_shape_with_id(
+    obj: InputShape,
+    object_id: u32,
+    default_tolerance: &shapes::Tolerance,
+) -> Result<Box<dyn shapes::Shape>, String> {
+    let tolerance = &obj.tolerance.map_or(*default_tolerance, Into::into);
+    validate_tolerance(tolerance)?;
+
+    if let Some(input_cylinder) = obj.cylinder {
+        Ok(create_cylinder(*input_cylinder, object_id, tolerance))
+    } else if let Some(input_box) = obj.oriented_box {
+        Ok(create_box(*input_box, object_id, tolerance))
+    } else if let Some(input_sphere) = obj.sphere {
+        Ok(create_sphere(*input_sphere, object_id, tolerance))
+    } else if let Some(input_cone) = obj.cone {
+        Ok(create_cone(*input_cone, object_id, tolerance))
+    } else if let Some(input_torus_segment) = obj.torus_segment {
+        Ok(create_torus_segment(
+            *input_torus_segment,
+            object_id,
+            tolerance,
+        ))
+    } else if let Some(input_capsule) = obj.capsule {
+        Ok(create_capsule(*input_capsule, object_id, tolerance))
+    } else if let Some(input_pipe_run) = obj.pipe_run {
+        Ok(create_pipe_run(*input_pipe_run, object_id, tolerance)?)
+    } else if let Some(input_convex_polyhedron) = obj.convex_polyhedron {
+        Ok(create_convex_polyhedron(
+            *input_convex_polyhedron,
+            object_id,
+            tolerance,
+        )?)
+    } else if let Some(input_prism) = obj.prism {
+        Ok(create_prism(*input_prism, object_id, tolerance)?)
+    } else if let Some(input_triangle_mesh) = obj.triangle_mesh {
+        Ok(create_triangle_mesh(
+            *input_triangle_mesh,
+            object_id,
+            tolerance,
+        )?)
+    } else if let Some(input_composite) = obj.composite {
+        Ok(create_composite(*input_composite, object_id, tolerance)?)
+    } else if let Some(input_ellipsoid) = obj.ellipsoid {
+        Ok(create_ellipsoid(*input_ellipsoid, object_id, tolerance))
+    } else {
+        Err("Unrecognized geometry type found while parsing".to_string())
+    }
+}
+
+pub fn validate_tolerance(tolerance: &shapes::Tolerance) -> Result<(), String> {
+    if tolerance
+        .relative_factor
+        .is_some_and(|factor| !(factor > 0.0 && factor.is_finite()))
+    {
+        return Err("Tolerance relative factor must be positive".to_string());
+    }
+    if tolerance
+        .absolute_margin
+        .is_some_and(|margin| !(margin >= 0.0 && margin.is_finite()))
+    {
+        return Err("Tolerance absolute margin must not be negative".to_string());
+    }
+    Ok(())
+}
+
+pub fn try_parse_overlap_resolution(input: Option<String>) -> Result<OverlapResolution, String> {
+    match input.as_deref() {
+        None => Ok(Default::default()),
+        Some("first_wins") => Ok(OverlapResolution::FirstShape),
+        Some("last_wins") => Ok(OverlapResolution::LastShape),
+        Some("smallest_volume_wins") => Ok(OverlapResolution::SmallestVolume),
+        Some("closest_to_center_wins") => Ok(OverlapResolution::ClosestCenter),
+        Some(other) => Err(format!("Unrecognized overlap resolution policy: {}", other)),
+    }
+}
+
+pub fn remove_tolerances(input: &mut InputShape) {
+    input.tolerance = None;
+    if let Some(composite) = input.composite.as_mut() {
+        composite.children.iter_mut().for_each(remove_tolerances);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::{
+        create_shape, InputComposite, InputCompositeOperation, InputConvexPolyhedron,
+        InputCylinder, InputOrientedBox, InputPrism, InputShape, InputTriangleMesh,
+    };
+    use crate::shapes::Tolerance;
+
+    const BEAM_HALF_LENGTH: f64 = 5.0;
+    const BEAM_HALF_WIDTH: f64 = 0.025;
+
+    /// Corners of a thin beam along the x axis, with bit `i` of the index choosing the side
+    /// along axis `i`
+    fn create_beam_corners() -> Vec<[f64; 3]> {
+        (0..8)
+            .map(|i| {
+                let side = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+                [
+                    side(0) * BEAM_HALF_LENGTH,
+                    side(1) * BEAM_HALF_WIDTH,
+                    side(2) * BEAM_HALF_WIDTH,
+                ]
+            })
+            .collect()
+    }
+
+    /// The same beam as an oriented box, a convex polyhedron, a prism and a triangle mesh
+    fn create_beams() -> Vec<InputShape> {
+        let corners = create_beam_corners();
+        let mut inv_instance_matrix = [0.0; 16];
+        inv_instance_matrix[0] = 1.0 / BEAM_HALF_LENGTH;
+        inv_instance_matrix[5] = 1.0 / BEAM_HALF_WIDTH;
+        inv_instance_matrix[10] = 1.0 / BEAM_HALF_WIDTH;
+        inv_instance_matrix[15] = 1.0;
+
+        let triangles: [u32; 36] = [
+            0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4, 2, 6, 3, 3, 6, 7, 0, 4, 2, 2, 4,
+            6, 1, 3, 5, 3, 7, 5,
+        ];
+
+        vec![
+            InputShape {
+                object_id: Some(1),
+                oriented_box: Some(Box::new(InputOrientedBox {
+                    inv_instance_matrix,
+                })),
+                ..Default::default()
+            },
+            InputShape {
+                object_id: Some(1),
+                convex_polyhedron: Some(Box::new(InputConvexPolyhedron {
+                    half_spaces: None,
+                    vertices: Some(corners.clone()),
+                })),
+                ..Default::default()
+            },
+            InputShape {
+                object_id: Some(1),
+                prism: Some(Box::new(InputPrism {
+                    vertices: [0, 1, 3, 2].map(|i| corners[i]).to_vec(),
+                    axis: [0.0, 0.0, 1.0],
+                    min_height: -BEAM_HALF_WIDTH,
+                    max_height: BEAM_HALF_WIDTH,
+                })),
+                ..Default::default()
+            },
+            InputShape {
+                object_id: Some(1),
+                triangle_mesh: Some(Box::new(InputTriangleMesh {
+                    vertices: corners.concat(),
+                    indices: triangles.to_vec(),
+                })),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn thin_beams_grow_sideways_alike_for_all_shape_types() {
+        let tolerance = Tolerance::default();
+        let lateral_growth = tolerance.inflate(BEAM_HALF_WIDTH) - BEAM_HALF_WIDTH;
+        let length_growth = tolerance.inflate(BEAM_HALF_LENGTH) - BEAM_HALF_LENGTH;
+
+        for beam in create_beams() {
+            let shape = create_shape(beam, &tolerance).unwrap();
+
+            for axis in [1, 2] {
+                let mut inside = vec3(0.0, 0.0, 0.0);
+                inside[axis] = BEAM_HALF_WIDTH + 0.9 * lateral_growth;
+                let mut outside = vec3(0.0, 0.0, 0.0);
+                outside[axis] = BEAM_HALF_WIDTH + 1.1 * lateral_growth;
+
+                assert!(shape.contains_point(&inside));
+                assert!(!shape.contains_point(&outside));
+            }
+            assert!(shape.contains_point(&vec3(BEAM_HALF_LENGTH + 0.9 * length_growth, 0.0, 0.0)));
+            assert!(!shape.contains_point(&vec3(BEAM_HALF_LENGTH + 1.1 * length_growth, 0.0, 0.0)));
+        }
+    }
+
+    fn create_box_minus_cylinder() -> InputShape {
+        let unit_box = InputShape {
+            oriented_box: Some(Box::new(InputOrientedBox {
+                inv_instance_matrix: [
+                    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+                ],
+            })),
+            ..Default::default()
+        };
+        let hole = InputShape {
+            cylinder: Some(Box::new(InputCylinder {
+                center_a: [0.0, 0.0, -2.0],
+                center_b: [0.0, 0.0, 2.0],
+                radius: 0.5,
+            })),
+            ..Default::default()
+        };
+
+        InputShape {
+            object_id: Some(1),
+            composite: Some(Box::new(InputComposite {
+                operation: InputCompositeOperation::Difference,
+                children: vec![unit_box, hole],
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_composite_children_may_leave_out_object_id() {
+        let mut shape = create_box_minus_cylinder();
+        assert!(create_shape(create_box_minus_cylinder(), &Tolerance::default()).is_ok());
+
+        shape.object_id = None;
+        assert!(create_shape(shape, &Tolerance::default()).is_err());
+    }
+
+    #[test]
+    fn subtracted_children_are_not_inflated() {
+        let shape = create_shape(create_box_minus_cylinder(), &Tolerance::default()).unwrap();
+
+        // Just outside the hole, which the inflated cylinder would have covered
+        assert!(shape.contains_point(&vec3(0.52, 0.0, 0.0)));
+        assert!(!shape.contains_point(&vec3(0.48, 0.0, 0.0)));
+        // The box itself is still inflated
+        assert!(shape.contains_point(&vec3(1.04, 0.0, 0.0)));
+    }
+}
@@ -0,0 +1,14 @@
+/*!
+ * Copyright 2022 Cognite AS
+ */
+
+//! Shapes, point octree and object assignment used by `pointclouds-wasm`. Works on plain
+//! slices and `Vec`s, so it builds and tests natively with `cargo test`
+
+mod bvh;
+pub mod decoding;
+pub mod inputs;
+pub mod linalg;
+pub mod object_assignment;
+pub mod point_octree;
+pub mod shapes;
@@ -0,0 +1,155 @@
+use nalgebra_glm::{max2, min2, vec3, vec4, vec4_to_vec3, DMat4, DVec3, DVec4};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BoundingBox {
+    pub min: DVec3,
+    pub max: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vec3WithIndex {
+    pub vec: DVec3,
+    pub index: usize,
+}
+
+/// Returns two unit vectors that together with `unit_axis` form a right-handed orthonormal basis
+pub fn create_orthonormal_basis(unit_axis: &DVec3) -> (DVec3, DVec3) {
+    let helper_axis = if unit_axis.x.abs() < unit_axis.y.abs() {
+        vec3(1.0, 0.0, 0.0)
+    } else {
+        vec3(0.0, 1.0, 0.0)
+    };
+
+    let u = helper_axis.cross(unit_axis).normalize();
+    let v = unit_axis.cross(&u);
+
+    (u, v)
+}
+
+impl BoundingBox {
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min.x < other.max.x
+            && self.max.x > other.min.x
+            && self.min.y < other.max.y
+            && self.max.y > other.min.y
+            && self.min.z < other.max.z
+            && self.max.z > other.min.z
+    }
+
+    pub fn has_nan(&self) -> bool {
+        self.min
+            .iter()
+            .chain(self.max.iter())
+            .any(|value| value.is_nan())
+    }
+
+    pub fn add_point(&mut self, point: &DVec3) {
+        self.min = min2(&self.min, point);
+        self.max = max2(&self.max, point);
+    }
+
+    pub fn add_box(&mut self, other: &BoundingBox) {
+        self.min = min2(&self.min, &other.min);
+        self.max = max2(&self.max, &other.max);
+    }
+
+    /// The overlapping region of two boxes. Empty (with `min` > `max`) if they do not overlap
+    pub fn intersection(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: max2(&self.min, &other.min),
+            max: min2(&self.max, &other.max),
+        }
+    }
+
+    pub fn get_center(&self) -> DVec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Whether the ray `origin + t * direction` hits the box for some `t >= 0`,
+    /// given the component-wise inverse of `direction`
+    pub fn intersects_ray(&self, origin: &DVec3, inverse_direction: &DVec3) -> bool {
+        let mut t_min: f64 = 0.0;
+        let mut t_max = f64::INFINITY;
+
+        for dim in 0..3 {
+            let t_0 = (self.min[dim] - origin[dim]) * inverse_direction[dim];
+            let t_1 = (self.max[dim] - origin[dim]) * inverse_direction[dim];
+
+            t_min = t_min.max(t_0.min(t_1));
+            t_max = t_max.min(t_0.max(t_1));
+        }
+
+        t_min <= t_max
+    }
+
+    pub fn contains_point(&self, point: &DVec3) -> bool {
+        min2(&self.min, point) == self.min && max2(&self.max, point) == self.max
+    }
+
+    pub fn get_base_cube_corner(corner_index: u32) -> DVec4 {
+        vec4(
+            if (corner_index & 1) == 0 { -1.0 } else { 1.0 },
+            if (corner_index & 2) == 0 { -1.0 } else { 1.0 },
+            if (corner_index & 4) == 0 { -1.0 } else { 1.0 },
+            1.0,
+        )
+    }
+
+    pub fn get_transformed_base_cube(matrix: &DMat4) -> Self {
+        (0..8)
+            .map(|i: u32| {
+                let unit_corner = BoundingBox::get_base_cube_corner(i);
+                let transformed_corner = matrix * unit_corner;
+                vec4_to_vec3(&transformed_corner)
+            })
+            .collect()
+    }
+
+    /// Tight bounding box of a flat disc with the given unit normal
+    pub fn get_disc_bounding_box(center: &DVec3, unit_normal: &DVec3, radius: f64) -> Self {
+        let extent = vec3(
+            (1.0 - unit_normal.x * unit_normal.x).max(0.0).sqrt(),
+            (1.0 - unit_normal.y * unit_normal.y).max(0.0).sqrt(),
+            (1.0 - unit_normal.z * unit_normal.z).max(0.0).sqrt(),
+        ) * radius;
+
+        BoundingBox {
+            min: center - extent,
+            max: center + extent,
+        }
+    }
+
+    pub fn get_base_cube_bounding_box() -> Self {
+        let points = (0..8).map(|i| vec4_to_vec3(&BoundingBox::get_base_cube_corner(i)));
+        let min_point: DVec3 = points.clone().reduce(|v0, v1| min2(&v0, &v1)).unwrap();
+        let max_point: DVec3 = points.reduce(|v0, v1| max2(&v0, &v1)).unwrap();
+        BoundingBox {
+            min: min_point,
+            max: max_point,
+        }
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        BoundingBox {
+            min: vec3(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: vec3(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+}
+
+impl FromIterator<DVec3> for BoundingBox {
+    fn from_iter<T>(point_iter: T) -> Self
+    where
+        T: IntoIterator<Item = DVec3>,
+    {
+        let mut bounding_box: BoundingBox = Default::default();
+
+        point_iter
+            .into_iter()
+            .for_each(|p| bounding_box.add_point(&p));
+
+        bounding_box
+    }
+}
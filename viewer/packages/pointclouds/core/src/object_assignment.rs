@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+use crate::point_octree::PointOctree;
+use crate::shapes::Shape;
+
+/// Decides which object a point gets when it is inside several shapes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OverlapResolution {
+    /// The shape appearing first in the input wins
+    FirstShape,
+    /// The shape appearing last in the input wins
+    #[default]
+    LastShape,
+    /// The shape with the smallest volume wins. Ties go to the last shape
+    SmallestVolume,
+    /// The shape whose center (or center axis) is closest to the point wins. Ties go to the first shape
+    ClosestCenter,
+}
+
+/// Returns the index of the shape each point is assigned to, indexed by `Vec3WithIndex::index`.
+/// Points outside all shapes get `None`
+fn assign_shape_indices(
+    octree: &PointOctree,
+    shapes: &[Box<dyn Shape>],
+    overlap_resolution: OverlapResolution,
+) -> Vec<Option<usize>> {
+    let mut shape_indices: Vec<Option<usize>> = vec![None; octree.num_points()];
+
+    // Shapes are visited in ascending index order for each point
+    match overlap_resolution {
+        OverlapResolution::LastShape => {
+            octree.for_each_point_in_shapes(shapes, |point, shape_index| {
+                shape_indices[point.index] = Some(shape_index);
+            });
+        }
+        OverlapResolution::FirstShape => {
+            octree.for_each_point_in_shapes(shapes, |point, shape_index| {
+                shape_indices[point.index].get_or_insert(shape_index);
+            });
+        }
+        OverlapResolution::SmallestVolume => {
+            let volumes: Vec<f64> = shapes.iter().map(|shape| shape.get_volume()).collect();
+
+            octree.for_each_point_in_shapes(shapes, |point, shape_index| {
+                let current = &mut shape_indices[point.index];
+                if current
+                    .is_none_or(|current_index| volumes[shape_index] <= volumes[current_index])
+                {
+                    *current = Some(shape_index);
+                }
+            });
+        }
+        OverlapResolution::ClosestCenter => {
+            let mut best_distances = vec![f64::INFINITY; octree.num_points()];
+
+            octree.for_each_point_in_shapes(shapes, |point, shape_index| {
+                let distance = shapes[shape_index].get_distance_to_center(&point.vec);
+                if distance < best_distances[point.index] {
+                    best_distances[point.index] = distance;
+                    shape_indices[point.index] = Some(shape_index);
+                }
+            });
+        }
+    }
+
+    shape_indices
+}
+
+fn convert_object_ids<T: TryFrom<u32>>(shapes: &[Box<dyn Shape>]) -> Result<Vec<T>, String> {
+    shapes
+        .iter()
+        .map(|shape| {
+            T::try_from(shape.get_object_id()).map_err(|_| {
+                format!(
+                    "Object id {} is too large for the output array, use 32-bit object ids",
+                    shape.get_object_id()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the object id for each point in the octree, indexed by `Vec3WithIndex::index`.
+/// Points outside all shapes get id 0. Fails if an object id does not fit in `T`
+pub fn assign_object_ids<T>(
+    octree: &PointOctree,
+    shapes: &[Box<dyn Shape>],
+    overlap_resolution: OverlapResolution,
+) -> Result<Vec<T>, String>
+where
+    T: Copy + Default + TryFrom<u32>,
+{
+    let shape_object_ids = convert_object_ids::<T>(shapes)?;
+
+    Ok(assign_shape_indices(octree, shapes, overlap_resolution)
+        .into_iter()
+        .map(|shape_index| shape_index.map_or(T::default(), |i| shape_object_ids[i]))
+        .collect())
+}
+
+/// Object id and signed distance to the surface of the assigned shape for each point,
+/// both indexed by `Vec3WithIndex::index`
+#[derive(Debug, PartialEq)]
+pub struct ObjectIdsWithDistances {
+    pub object_ids: Vec<u32>,
+    /// NaN for points outside all shapes, and for shapes without a signed distance
+    pub distances: Vec<f32>,
+}
+
+/// Assigns points like `assign_object_ids`, but also measures the signed distance to the assigned
+/// shape. Distances are measured to `exact_shapes`, the shapes without tolerance, which must
+/// correspond one-to-one with `shapes`
+pub fn assign_object_ids_with_distances(
+    octree: &PointOctree,
+    shapes: &[Box<dyn Shape>],
+    exact_shapes: &[Box<dyn Shape>],
+    overlap_resolution: OverlapResolution,
+) -> ObjectIdsWithDistances {
+    let shape_indices = assign_shape_indices(octree, shapes, overlap_resolution);
+
+    let mut distances = vec![f32::NAN; octree.num_points()];
+    octree.points().iter().for_each(|point| {
+        if let Some(shape_index) = shape_indices[point.index] {
+            if let Some(distance) = exact_shapes[shape_index].get_signed_distance(&point.vec) {
+                distances[point.index] = distance as f32;
+            }
+        }
+    });
+
+    ObjectIdsWithDistances {
+        object_ids: shape_indices
+            .into_iter()
+            .map(|shape_index| shape_index.map_or(0, |i| shapes[i].get_object_id()))
+            .collect(),
+        distances,
+    }
+}
+
+/// Aggregate of the points assigned to one object, in the same coordinates as the shapes
+#[derive(Debug)]
+pub struct ObjectStatistics {
+    pub object_id: u32,
+    pub point_count: u32,
+    /// Tight box around the assigned points
+    pub bounding_box: BoundingBox,
+    pub centroid: DVec3,
+    /// Mean signed distance to the surface of the assigned shapes, over the points where it is
+    /// known. NaN if it is not known for any of the points
+    pub mean_distance: f64,
+}
+
+#[derive(Default)]
+struct StatisticsAccumulator {
+    point_count: u32,
+    bounding_box: BoundingBox,
+    position_sum: DVec3,
+    distance_sum: f64,
+    distance_count: u32,
+}
+
+/// Statistics for each object with at least one assigned point, in ascending object id order.
+/// Points with object id 0, i.e. outside all shapes, are not counted
+pub fn compute_object_statistics(
+    octree: &PointOctree,
+    assignment: &ObjectIdsWithDistances,
+) -> Vec<ObjectStatistics> {
+    let mut accumulators: BTreeMap<u32, StatisticsAccumulator> = BTreeMap::new();
+
+    octree.points().iter().for_each(|point| {
+        let object_id = assignment.object_ids[point.index];
+        if object_id == 0 {
+            return;
+        }
+
+        let accumulator = accumulators.entry(object_id).or_default();
+        accumulator.point_count += 1;
+        accumulator.bounding_box.add_point(&point.vec);
+        accumulator.position_sum += point.vec;
+
+        let distance = assignment.distances[point.index];
+        if !distance.is_nan() {
+            accumulator.distance_sum += distance as f64;
+            accumulator.distance_count += 1;
+        }
+    });
+
+    accumulators
+        .into_iter()
+        .map(|(object_id, accumulator)| ObjectStatistics {
+            object_id,
+            point_count: accumulator.point_count,
+            bounding_box: accumulator.bounding_box,
+            centroid: accumulator.position_sum / accumulator.point_count as f64,
+            mean_distance: if accumulator.distance_count > 0 {
+                accumulator.distance_sum / accumulator.distance_count as f64
+            } else {
+                f64::NAN
+            },
+        })
+        .collect()
+}
+
+/// Every object each point belongs to, in compressed sparse row layout. The object ids of point `i`
+/// are `object_ids[offsets[i]..offsets[i + 1]]`, in ascending order and without duplicates
+#[derive(Debug, PartialEq)]
+pub struct ObjectMemberships {
+    pub offsets: Vec<u32>,
+    pub object_ids: Vec<u32>,
+}
+
+pub fn assign_all_object_ids(octree: &PointOctree, shapes: &[Box<dyn Shape>]) -> ObjectMemberships {
+    let mut memberships: Vec<(usize, u32)> = Vec::new();
+
+    octree.for_each_point_in_shapes(shapes, |point, shape_index| {
+        memberships.push((point.index, shapes[shape_index].get_object_id()));
+    });
+
+    memberships.sort_unstable();
+    memberships.dedup();
+
+    let mut offsets = vec![0; octree.num_points() + 1];
+    memberships
+        .iter()
+        .for_each(|(point_index, _)| offsets[point_index + 1] += 1);
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+
+    ObjectMemberships {
+        offsets,
+        object_ids: memberships
+            .into_iter()
+            .map(|(_, object_id)| object_id)
+            .collect(),
+    }
+}
+
+/// Sorted indices (`Vec3WithIndex::index`) of the points inside any of the given shapes.
+/// Other shapes are not considered, so points may also be inside shapes of other objects
+pub fn find_point_indices<'a>(
+    octree: &PointOctree,
+    shapes: impl IntoIterator<Item = &'a dyn Shape>,
+) -> Vec<u32> {
+    let mut point_indices = Vec::new();
+
+    shapes.into_iter().for_each(|shape| {
+        octree.for_each_point_in_shape(shape, |point| point_indices.push(point.index as u32));
+    });
+
+    point_indices.sort_unstable();
+    point_indices.dedup();
+    point_indices
+}
+
+/// Sorted indices of the points inside any of the shapes with the given object id
+pub fn find_object_point_indices(
+    octree: &PointOctree,
+    shapes: &[Box<dyn Shape>],
+    object_id: u32,
+) -> Vec<u32> {
+    find_point_indices(
+        octree,
+        shapes
+            .iter()
+            .filter(|shape| shape.get_object_id() == object_id)
+            .map(|shape| shape.as_ref()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::{
+        assign_all_object_ids, assign_object_ids, assign_object_ids_with_distances,
+        compute_object_statistics, find_object_point_indices, ObjectMemberships, OverlapResolution,
+    };
+    use crate::linalg::{BoundingBox, Vec3WithIndex};
+    use crate::point_octree::PointOctree;
+    use crate::shapes::{Cylinder, Shape, Sphere};
+
+    fn create_octree_with_points_along_x_axis() -> PointOctree {
+        let points = (0..5)
+            .map(|i| Vec3WithIndex {
+                vec: vec3(i as f64 - 2.0, 0.0, 0.0),
+                index: i,
+            })
+            .collect();
+
+        PointOctree::new(
+            BoundingBox {
+                min: vec3(-4.0, -4.0, -4.0),
+                max: vec3(4.0, 4.0, 4.0),
+            },
+            points,
+        )
+    }
+
+    /// A big sphere covering all points, and a small one covering the two points at x = 1 and x = 2
+    fn create_overlapping_spheres() -> Vec<Box<dyn Shape>> {
+        vec![
+            Box::new(Sphere::new(vec3(1.5, 0.0, 0.0), 0.8, 1)),
+            Box::new(Sphere::new(vec3(-1.0, 0.0, 0.0), 3.5, 2)),
+        ]
+    }
+
+    #[test]
+    fn first_and_last_wins_depend_on_input_order() {
+        let octree = create_octree_with_points_along_x_axis();
+        let shapes = create_overlapping_spheres();
+
+        assert_eq!(
+            assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::FirstShape).unwrap(),
+            vec![2, 2, 2, 1, 1]
+        );
+        assert_eq!(
+            assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::LastShape).unwrap(),
+            vec![2, 2, 2, 2, 2]
+        );
+    }
+
+    #[test]
+    fn smallest_volume_wins_regardless_of_order() {
+        let octree = create_octree_with_points_along_x_axis();
+        let mut shapes = create_overlapping_spheres();
+
+        let expected = vec![2, 2, 2, 1, 1];
+        assert_eq!(
+            assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::SmallestVolume).unwrap(),
+            expected
+        );
+
+        shapes.reverse();
+        assert_eq!(
+            assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::SmallestVolume).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn closest_center_wins_per_point() {
+        let octree = create_octree_with_points_along_x_axis();
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Sphere::new(vec3(-1.0, 0.0, 0.0), 5.0, 1)),
+            Box::new(Sphere::new(vec3(1.5, 0.0, 0.0), 5.0, 2)),
+        ];
+
+        assert_eq!(
+            assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::ClosestCenter).unwrap(),
+            vec![1, 1, 1, 2, 2]
+        );
+    }
+
+    #[test]
+    fn all_memberships_are_reported_once_per_point() {
+        let octree = create_octree_with_points_along_x_axis();
+        let mut shapes = create_overlapping_spheres();
+        shapes.push(Box::new(Sphere::new(vec3(2.0, 0.0, 0.0), 0.5, 1)));
+
+        assert_eq!(
+            assign_all_object_ids(&octree, &shapes),
+            ObjectMemberships {
+                offsets: vec![0, 1, 2, 3, 5, 7],
+                object_ids: vec![2, 2, 2, 1, 2, 1, 2],
+            }
+        );
+    }
+
+    #[test]
+    fn large_object_ids_require_32_bit_output() {
+        let octree = create_octree_with_points_along_x_axis();
+        let shapes: Vec<Box<dyn Shape>> =
+            vec![Box::new(Sphere::new(vec3(0.0, 0.0, 0.0), 0.5, 70_000))];
+
+        assert!(assign_object_ids::<u16>(&octree, &shapes, OverlapResolution::LastShape).is_err());
+        assert_eq!(
+            assign_object_ids::<u32>(&octree, &shapes, OverlapResolution::LastShape).unwrap(),
+            vec![0, 0, 70_000, 0, 0]
+        );
+    }
+
+    #[test]
+    fn distances_are_measured_to_the_exact_assigned_shape() {
+        let octree = create_octree_with_points_along_x_axis();
+        let create_cylinder = |radius| -> Vec<Box<dyn Shape>> {
+            vec![
+                Box::new(Cylinder::new(
+                    vec3(-1.5, 0.0, 0.0),
+                    vec3(1.5, 0.0, 0.0),
+                    radius,
+                    3,
+                )),
+                Box::new(Sphere::new(vec3(-2.0, 0.0, 0.0), 0.1, 4)),
+            ]
+        };
+
+        let result = assign_object_ids_with_distances(
+            &octree,
+            &create_cylinder(1.0),
+            &create_cylinder(0.5),
+            OverlapResolution::LastShape,
+        );
+
+        assert_eq!(result.object_ids, vec![4, 3, 3, 3, 0]);
+        assert!(result.distances[0].is_nan());
+        assert_eq!(&result.distances[1..4], &[-0.5, -0.5, -0.5]);
+        assert!(result.distances[4].is_nan());
+    }
+
+    #[test]
+    fn statistics_aggregate_the_assigned_points_per_object() {
+        let octree = create_octree_with_points_along_x_axis();
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Cylinder::new(
+                vec3(-3.0, 0.0, 0.0),
+                vec3(0.5, 0.0, 0.0),
+                1.0,
+                5,
+            )),
+            Box::new(Sphere::new(vec3(1.5, 0.0, 0.0), 0.8, 7)),
+        ];
+
+        let assignment = assign_object_ids_with_distances(
+            &octree,
+            &shapes,
+            &shapes,
+            OverlapResolution::LastShape,
+        );
+        let statistics = compute_object_statistics(&octree, &assignment);
+
+        assert_eq!(statistics.len(), 2);
+
+        assert_eq!(statistics[0].object_id, 5);
+        assert_eq!(statistics[0].point_count, 3);
+        assert_eq!(statistics[0].bounding_box.min, vec3(-2.0, 0.0, 0.0));
+        assert_eq!(statistics[0].bounding_box.max, vec3(0.0, 0.0, 0.0));
+        assert!((statistics[0].centroid - vec3(-1.0, 0.0, 0.0)).magnitude() < 1e-9);
+        assert!((statistics[0].mean_distance + 2.5 / 3.0).abs() < 1e-6);
+
+        assert_eq!(statistics[1].object_id, 7);
+        assert_eq!(statistics[1].point_count, 2);
+        assert!((statistics[1].centroid - vec3(1.5, 0.0, 0.0)).magnitude() < 1e-9);
+        assert!(statistics[1].mean_distance.is_nan());
+    }
+
+    #[test]
+    fn object_point_indices_cover_all_shapes_of_the_object() {
+        let octree = create_octree_with_points_along_x_axis();
+        let mut shapes = create_overlapping_spheres();
+        shapes.push(Box::new(Sphere::new(vec3(-2.0, 0.0, 0.0), 0.5, 1)));
+
+        assert_eq!(
+            find_object_point_indices(&octree, &shapes, 1),
+            vec![0, 3, 4]
+        );
+        assert_eq!(
+            find_object_point_indices(&octree, &shapes, 2),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert!(find_object_point_indices(&octree, &shapes, 3).is_empty());
+    }
+}
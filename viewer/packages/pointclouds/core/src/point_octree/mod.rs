@@ -1,4 +1,5 @@
 mod octree_node;
+#[allow(clippy::module_inception)]
 pub mod point_octree;
 
 pub use point_octree::*;
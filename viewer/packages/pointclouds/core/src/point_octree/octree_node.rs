@@ -3,65 +3,100 @@ use crate::linalg::{BoundingBox, Vec3WithIndex};
 pub const MAX_POINTS_PER_NODE: usize = 1_000;
 pub const MIN_OCTREE_NODE_SIZE: f64 = 0.0625;
 
+use std::ops::Range;
+
 use nalgebra_glm::DVec3;
 
 use crate::shapes::Shape;
 
 #[derive(Debug)]
-enum OctreeNodeContent<'a> {
-    Children(Box<[OctreeNode<'a>; 8]>),
-    Points(&'a [Vec3WithIndex]),
+enum OctreeNodeContent {
+    Children(Box<[OctreeNode; 8]>),
+    Points(Range<usize>),
 }
 
+/// A node in the point octree. Leaf nodes refer to a range of the point storage
+/// owned by the containing `PointOctree`, which is sorted so that each leaf's points are contiguous
 #[derive(Debug)]
-pub struct OctreeNode<'a> {
-    content: OctreeNodeContent<'a>,
+pub struct OctreeNode {
+    content: OctreeNodeContent,
     bounding_box: BoundingBox,
 }
 
-impl<'a> OctreeNode<'a> {
-    pub fn new(bounding_box: BoundingBox, points: &'a mut [Vec3WithIndex]) -> OctreeNode<'a> {
+impl OctreeNode {
+    /// Builds a node for `points`, which must be the sub-slice starting at `offset` in the
+    /// octree's point storage. The points are reordered in place
+    pub fn new(
+        bounding_box: BoundingBox,
+        points: &mut [Vec3WithIndex],
+        offset: usize,
+    ) -> OctreeNode {
         if points.len() <= MAX_POINTS_PER_NODE
             || bounding_box.max.x - bounding_box.min.x < MIN_OCTREE_NODE_SIZE
         {
             OctreeNode {
-                content: OctreeNodeContent::Points(points),
-                bounding_box: bounding_box,
+                content: OctreeNodeContent::Points(offset..offset + points.len()),
+                bounding_box,
             }
         } else {
-            let children = split(points, bounding_box);
+            let children = split(points, bounding_box, offset);
             OctreeNode {
                 content: OctreeNodeContent::Children(children),
-                bounding_box: bounding_box,
+                bounding_box,
             }
         }
     }
 
-    pub fn assign_object_ids(
+    /// Calls `visit` for each point inside `shape`, only visiting nodes that overlap
+    /// at least one of `bounding_boxes`
+    pub fn for_each_point_in_shape(
         &self,
-        bounding_box: &BoundingBox,
-        shape: &Box<dyn Shape>,
-        object_ids: &js_sys::Uint16Array,
-    ) -> () {
+        points: &[Vec3WithIndex],
+        bounding_boxes: &[BoundingBox],
+        shape: &dyn Shape,
+        visit: &mut impl FnMut(&Vec3WithIndex),
+    ) {
         match &self.content {
             OctreeNodeContent::Children(children) => children.iter().for_each(|child| {
-                if child.bounding_box.overlaps(bounding_box) {
-                    child.assign_object_ids(bounding_box, shape, object_ids);
+                if bounding_boxes
+                    .iter()
+                    .any(|bounding_box| child.bounding_box.overlaps(bounding_box))
+                {
+                    child.for_each_point_in_shape(points, bounding_boxes, shape, visit);
                 }
             }),
-            OctreeNodeContent::Points(points) => points.iter().for_each(|point| {
+            OctreeNodeContent::Points(range) => points[range.clone()].iter().for_each(|point| {
                 if shape.contains_point(&point.vec) {
-                    object_ids.set_index(point.index as u32, shape.get_object_id() as u16);
+                    visit(point);
                 }
             }),
         }
     }
+
+    /// Calls `visit` with the bounding box and points of each non-empty leaf
+    pub fn for_each_leaf<'a>(
+        &self,
+        points: &'a [Vec3WithIndex],
+        visit: &mut impl FnMut(&BoundingBox, &'a [Vec3WithIndex]),
+    ) {
+        match &self.content {
+            OctreeNodeContent::Children(children) => children
+                .iter()
+                .for_each(|child| child.for_each_leaf(points, visit)),
+            OctreeNodeContent::Points(range) => {
+                if !range.is_empty() {
+                    visit(&self.bounding_box, &points[range.clone()]);
+                }
+            }
+        }
+    }
 }
 
-fn split<'a>(
-    points: &'a mut [Vec3WithIndex],
+fn split(
+    points: &mut [Vec3WithIndex],
     bounding_box: BoundingBox,
-) -> Box<[OctreeNode<'a>; 8]> {
+    offset: usize,
+) -> Box<[OctreeNode; 8]> {
     let middle = (bounding_box.min + bounding_box.max) / 2.0;
     let splits = find_splits(points, &middle);
 
@@ -69,22 +104,26 @@ fn split<'a>(
 
     let boxes = get_child_bounding_boxes(&bounding_box);
 
-    let split_maxes = get_split_ends(points, &splits);
+    let split_ends = get_split_ends(points, &splits);
 
-    let children: Box<[OctreeNode<'a>; 8]> =
-        Box::<[OctreeNode<'a>; 8]>::new(std::array::from_fn(|child_index| unsafe {
-            let ptr = points.as_mut_ptr().add(splits[child_index]);
-            let slice =
-                std::slice::from_raw_parts_mut(ptr, split_maxes[child_index] - splits[child_index]);
+    let mut remaining_points = points;
+    let children: [OctreeNode; 8] = std::array::from_fn(|child_index| {
+        let (child_points, rest) = std::mem::take(&mut remaining_points)
+            .split_at_mut(split_ends[child_index] - splits[child_index]);
+        remaining_points = rest;
 
-            OctreeNode::new(boxes[child_index], slice)
-        }));
+        OctreeNode::new(
+            boxes[child_index],
+            child_points,
+            offset + splits[child_index],
+        )
+    });
 
-    children
+    Box::new(children)
 }
 
-fn get_split_ends<'a>(points: &'a [Vec3WithIndex], splits: &[usize; 8]) -> [usize; 8] {
-    let mut split_maxes = splits.clone();
+fn get_split_ends(points: &[Vec3WithIndex], splits: &[usize; 8]) -> [usize; 8] {
+    let mut split_maxes = *splits;
     split_maxes.rotate_left(1);
     split_maxes[7] = points.len();
 
@@ -94,7 +133,7 @@ fn get_split_ends<'a>(points: &'a [Vec3WithIndex], splits: &[usize; 8]) -> [usiz
 fn find_splits(points: &mut [Vec3WithIndex], middle: &DVec3) -> [usize; 8] {
     let mut sector_counts = [0; 8];
     for point in points.iter() {
-        let index = get_octree_child_index(&point.vec, &middle);
+        let index = get_octree_child_index(&point.vec, middle);
         sector_counts[index] += 1;
     }
 
@@ -153,12 +192,8 @@ fn sort_points_into_partitions_until_specific_partition_filled(
 
 /// Takes the points slice and a starting index for each of the eight octree node children slices, and groups
 /// the points into their corresponding child's slice. It does not allocate a new vector
-fn sort_points_into_sectors(
-    points: &mut [Vec3WithIndex],
-    splits: [usize; 8],
-    middle: &DVec3,
-) -> () {
-    let mut offsets = splits.clone();
+fn sort_points_into_sectors(points: &mut [Vec3WithIndex], splits: [usize; 8], middle: &DVec3) {
+    let mut offsets = splits;
     let partition_end_points = get_split_ends(points, &splits);
 
     for current_partition in 0..8 {
@@ -182,7 +217,7 @@ fn get_child_bounding_boxes(bounding_box: &BoundingBox) -> [BoundingBox; 8] {
 
     let middle = (bounding_box.min + bounding_box.max) / 2.0;
 
-    for i in 0..8 {
+    for (i, child_box) in boxes.iter_mut().enumerate() {
         let mut min: DVec3 = Default::default();
         let mut max: DVec3 = Default::default();
 
@@ -204,7 +239,7 @@ fn get_child_bounding_boxes(bounding_box: &BoundingBox) -> [BoundingBox; 8] {
             (middle.z, bounding_box.max.z)
         };
 
-        boxes[i] = BoundingBox { min: min, max: max };
+        *child_box = BoundingBox { min, max };
     }
 
     boxes
@@ -212,7 +247,6 @@ fn get_child_bounding_boxes(bounding_box: &BoundingBox) -> [BoundingBox; 8] {
 
 #[cfg(test)]
 mod tests {
-    use wasm_bindgen_test::wasm_bindgen_test;
 
     use rand::prelude::*;
     use rand_chacha::ChaCha8Rng;
@@ -225,21 +259,21 @@ mod tests {
     };
     use super::{BoundingBox, Vec3WithIndex};
 
-    #[wasm_bindgen_test]
+    #[test]
     fn test_point_in_first_octant_gets_index_0() {
         let point = vec3(-0.1, -0.4, -0.2);
         let middle = vec3(0.0, 0.0, 0.0);
         assert_eq!(get_octree_child_index(&point, &middle), 0);
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn test_point_in_last_octant_gets_index_7() {
         let point = vec3(0.1, 0.4, 0.2);
         let middle = vec3(0.0, 0.0, 0.0);
         assert_eq!(get_octree_child_index(&point, &middle), 7);
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn test_child_box_centers_average_to_parent_center() {
         let bounding_box = BoundingBox {
             min: vec3(-2.0, -1.0, 0.0),
@@ -252,7 +286,7 @@ mod tests {
 
         let mut accumulative_middle: DVec3 = Default::default();
         for child in child_boxes.iter() {
-            accumulative_middle = accumulative_middle + (child.min + child.max) / 2.0;
+            accumulative_middle += (child.min + child.max) / 2.0;
         }
 
         let average_middle = accumulative_middle / (child_boxes.len() as f64);
@@ -260,7 +294,7 @@ mod tests {
         assert!(comp_max(&(average_middle - middle).abs()) < epsilon());
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn test_sector_inplace_sorting() {
         const NUM_POINTS: usize = 300;
 
@@ -272,9 +306,9 @@ mod tests {
 
         for i in 0..NUM_POINTS {
             let p = vec3(
-                rng.gen_range(-1.0..1.0),
-                rng.gen_range(-1.0..1.0),
-                rng.gen_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
             );
             points.push(Vec3WithIndex { vec: p, index: i });
         }
@@ -289,11 +323,8 @@ mod tests {
 
         for sector_index in 0..8 {
             let max_ind = split_ends[sector_index];
-            for point_index in splits[sector_index]..max_ind {
-                assert_eq!(
-                    get_octree_child_index(&points[point_index].vec, &middle),
-                    sector_index
-                );
+            for point in &points[splits[sector_index]..max_ind] {
+                assert_eq!(get_octree_child_index(&point.vec, &middle), sector_index);
                 num_points_checked += 1;
             }
         }
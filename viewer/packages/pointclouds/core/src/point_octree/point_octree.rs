@@ -0,0 +1,272 @@
+use crate::bvh::Bvh;
+use crate::linalg::{BoundingBox, Vec3WithIndex};
+
+use super::octree_node::OctreeNode;
+use std::vec::Vec;
+
+use crate::shapes::Shape;
+
+/// Octree over a point set. Owns its points, so it can be built once and queried many times
+pub struct PointOctree {
+    points: Vec<Vec3WithIndex>,
+    root: OctreeNode,
+}
+
+impl PointOctree {
+    pub fn new(bounding_box: BoundingBox, mut points: Vec<Vec3WithIndex>) -> PointOctree {
+        let root = OctreeNode::new(bounding_box, &mut points, 0);
+        PointOctree { points, root }
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.points.len()
+    }
+
+    /// All points of the octree, in no particular order
+    pub fn points(&self) -> &[Vec3WithIndex] {
+        &self.points
+    }
+
+    pub fn for_each_point_in_shape(
+        &self,
+        shape: &dyn Shape,
+        mut visit: impl FnMut(&Vec3WithIndex),
+    ) {
+        self.root.for_each_point_in_shape(
+            &self.points,
+            &shape.create_bounding_boxes(),
+            shape,
+            &mut visit,
+        );
+    }
+
+    /// Calls `visit` with each point and the index of each shape containing it. Shapes are looked
+    /// up per octree leaf in a BVH over their culling boxes, so the cost scales with the number of
+    /// leaf/shape overlaps rather than with the number of shapes. All calls for a given point
+    /// are made in ascending shape index order
+    pub fn for_each_point_in_shapes(
+        &self,
+        shapes: &[Box<dyn Shape>],
+        mut visit: impl FnMut(&Vec3WithIndex, usize),
+    ) {
+        let (culling_boxes, box_shape_indices): (Vec<BoundingBox>, Vec<usize>) = shapes
+            .iter()
+            .enumerate()
+            .flat_map(|(shape_index, shape)| {
+                shape
+                    .create_bounding_boxes()
+                    .into_iter()
+                    .map(move |bounding_box| (bounding_box, shape_index))
+            })
+            .unzip();
+        let bvh = Bvh::new(&culling_boxes);
+
+        let mut leaf_shape_indices = Vec::new();
+        self.root
+            .for_each_leaf(&self.points, &mut |leaf_box, leaf_points| {
+                leaf_shape_indices.clear();
+                bvh.for_each_overlapping(leaf_box, |box_index| {
+                    if culling_boxes[box_index].overlaps(leaf_box) {
+                        leaf_shape_indices.push(box_shape_indices[box_index]);
+                    }
+                });
+                leaf_shape_indices.sort_unstable();
+                leaf_shape_indices.dedup();
+
+                leaf_points.iter().for_each(|point| {
+                    leaf_shape_indices.iter().for_each(|&shape_index| {
+                        if shapes[shape_index].contains_point(&point.vec) {
+                            visit(point, shape_index);
+                        }
+                    });
+                });
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::PointOctree;
+    use crate::linalg::{BoundingBox, Vec3WithIndex};
+    use crate::shapes::{Cylinder, OrientedBox, Shape, Sphere};
+
+    use nalgebra_glm::{translate, vec3, DMat4};
+
+    use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
+
+    fn create_random_points_in_base_box(num_points: u32) -> Vec<Vec3WithIndex> {
+        let mut rng = ChaCha8Rng::seed_from_u64(0xbaadf00d);
+
+        let mut points = Vec::<Vec3WithIndex>::with_capacity(num_points as usize);
+
+        let base_box = BoundingBox::get_base_cube_bounding_box();
+
+        for i in 0..num_points {
+            points.push(Vec3WithIndex {
+                vec: vec3(
+                    rng.random_range(base_box.min.x..base_box.max.x),
+                    rng.random_range(base_box.min.y..base_box.max.y),
+                    rng.random_range(base_box.min.z..base_box.max.z),
+                ),
+                index: i as usize,
+            });
+        }
+
+        points
+    }
+
+    #[test]
+    fn all_points_returned_for_all_enclosing_box_shape() {
+        const NUM_POINTS: u32 = 1_000;
+        const OBJECT_ID: u32 = 42;
+
+        let points = create_random_points_in_base_box(NUM_POINTS);
+
+        let shape: Box<dyn Shape> =
+            Box::<OrientedBox>::new(OrientedBox::new(DMat4::identity(), OBJECT_ID));
+        let bounding_box = BoundingBox::get_transformed_base_cube(&DMat4::identity());
+        let mut array = vec![0; NUM_POINTS as usize];
+
+        let octree = PointOctree::new(bounding_box, points);
+        octree.for_each_point_in_shape(shape.as_ref(), |point| {
+            array[point.index] = shape.get_object_id();
+        });
+
+        for i in 0..NUM_POINTS {
+            let set_object_id = array[i as usize];
+            assert_eq!(set_object_id, OBJECT_ID);
+        }
+    }
+
+    #[test]
+    fn no_points_returned_for_non_overlapping_shape() {
+        const NUM_POINTS: u32 = 1_000;
+        const OBJECT_ID: u32 = 42;
+
+        let points = create_random_points_in_base_box(NUM_POINTS);
+
+        let box_matrix = translate(&DMat4::identity(), &vec3(2.0, 0.0, 0.0));
+        let bounding_box = BoundingBox::get_transformed_base_cube(&box_matrix);
+        let shape: Box<dyn Shape> =
+            Box::<OrientedBox>::new(OrientedBox::new(box_matrix, OBJECT_ID));
+        let mut array = vec![0; NUM_POINTS as usize];
+
+        let octree = PointOctree::new(bounding_box, points);
+        octree.for_each_point_in_shape(shape.as_ref(), |point| {
+            array[point.index] = shape.get_object_id();
+        });
+
+        for i in 0..NUM_POINTS {
+            let set_object_id = array[i as usize];
+            assert_eq!(set_object_id, 0);
+        }
+    }
+
+    #[test]
+    fn octree_can_be_queried_with_several_shapes() {
+        const NUM_POINTS: u32 = 1_000;
+
+        let points = create_random_points_in_base_box(NUM_POINTS);
+
+        let left_matrix = translate(&DMat4::identity(), &vec3(1.0, 0.0, 0.0));
+        let right_matrix = translate(&DMat4::identity(), &vec3(-1.0, 0.0, 0.0));
+        let left_shape = OrientedBox::new(left_matrix, 1);
+        let right_shape = OrientedBox::new(right_matrix, 2);
+        let mut array = vec![0; NUM_POINTS as usize];
+
+        let octree = PointOctree::new(BoundingBox::get_base_cube_bounding_box(), points.clone());
+        octree.for_each_point_in_shape(&left_shape, |point| array[point.index] = 1);
+        octree.for_each_point_in_shape(&right_shape, |point| array[point.index] = 2);
+
+        assert_eq!(octree.num_points(), NUM_POINTS as usize);
+        for point in points {
+            let expected_id = if right_shape.contains_point(&point.vec) {
+                2
+            } else if left_shape.contains_point(&point.vec) {
+                1
+            } else {
+                0
+            };
+            assert_eq!(array[point.index], expected_id);
+        }
+    }
+
+    #[test]
+    fn shape_bvh_query_matches_querying_each_shape() {
+        const NUM_POINTS: u32 = 20_000;
+
+        let points = create_random_points_in_base_box(NUM_POINTS);
+        let octree = PointOctree::new(BoundingBox::get_base_cube_bounding_box(), points);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0xdecafbad);
+        let shapes: Vec<Box<dyn Shape>> = (0..200)
+            .map(|i| -> Box<dyn Shape> {
+                let center = vec3(
+                    rng.random_range(-1.2..1.2),
+                    rng.random_range(-1.2..1.2),
+                    rng.random_range(-1.2..1.2),
+                );
+                Box::new(Sphere::new(center, rng.random_range(0.01..0.3), i))
+            })
+            .collect();
+
+        let mut expected = vec![];
+        shapes.iter().enumerate().for_each(|(shape_index, shape)| {
+            octree.for_each_point_in_shape(shape.as_ref(), |point| {
+                expected.push((point.index, shape_index));
+            });
+        });
+        expected.sort_unstable();
+
+        let mut found = vec![];
+        octree.for_each_point_in_shapes(&shapes, |point, shape_index| {
+            if let Some(&(last_index, last_shape_index)) = found.last() {
+                assert!(last_index != point.index || last_shape_index < shape_index);
+            }
+            found.push((point.index, shape_index));
+        });
+        found.sort_unstable();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn degenerate_shape_does_not_hide_points_of_other_shapes() {
+        const NUM_POINTS: u32 = 5_000;
+
+        let points = create_random_points_in_base_box(NUM_POINTS);
+        let octree = PointOctree::new(BoundingBox::get_base_cube_bounding_box(), points);
+
+        // The zero-length cylinder has a NaN bounding box, and is placed after the spheres
+        let mut shapes: Vec<Box<dyn Shape>> = (0..6)
+            .map(|i| -> Box<dyn Shape> {
+                let x = -0.75 + 0.3 * i as f64;
+                Box::new(Sphere::new(vec3(x, 0.0, 0.0), 0.25, i + 1))
+            })
+            .collect();
+        let center = vec3(0.0, 0.5, 0.0);
+        shapes.push(Box::new(Cylinder::new(center, center, 0.2, 7)));
+
+        let mut found = vec![];
+        octree.for_each_point_in_shapes(&shapes, |point, shape_index| {
+            found.push((point.index, shape_index));
+        });
+        found.sort_unstable();
+
+        let mut expected = vec![];
+        octree.points().iter().for_each(|point| {
+            (0..6).for_each(|shape_index| {
+                if shapes[shape_index].contains_point(&point.vec) {
+                    expected.push((point.index, shape_index));
+                }
+            });
+        });
+        expected.sort_unstable();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+}
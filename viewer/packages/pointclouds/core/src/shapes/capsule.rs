@@ -0,0 +1,95 @@
+use std::f64::consts::PI;
+
+use nalgebra_glm::{dot, vec3, DVec3};
+
+use crate::linalg::BoundingBox;
+use crate::shapes::shape::Shape;
+
+/// Cylinder with hemispherical caps, i.e. all points closer than `radius` to the line segment
+/// between `center_a` and `center_b`
+pub struct Capsule {
+    center_a: DVec3,
+    center_b: DVec3,
+    radius: f64,
+    object_id: u32,
+}
+
+impl Capsule {
+    pub fn new(center_a: DVec3, center_b: DVec3, radius: f64, object_id: u32) -> Self {
+        Capsule {
+            center_a,
+            center_b,
+            radius,
+            object_id,
+        }
+    }
+
+    fn get_distance_to_axis_segment(&self, point: &DVec3) -> f64 {
+        let axis_vec = self.center_b - self.center_a;
+        let axis_length_sq = axis_vec.magnitude_squared();
+
+        let t = if axis_length_sq > 0.0 {
+            (dot(&(point - self.center_a), &axis_vec) / axis_length_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        (point - (self.center_a + axis_vec * t)).magnitude()
+    }
+}
+
+impl Shape for Capsule {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        self.get_distance_to_axis_segment(point) < self.radius
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let radius_vec = vec3(self.radius, self.radius, self.radius);
+        let mut bounding_box: BoundingBox = [self.center_a, self.center_b].into_iter().collect();
+        bounding_box.min -= radius_vec;
+        bounding_box.max += radius_vec;
+
+        bounding_box
+    }
+
+    fn get_volume(&self) -> f64 {
+        let height = (self.center_b - self.center_a).magnitude();
+        PI * self.radius * self.radius * (height + 4.0 / 3.0 * self.radius)
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        self.get_distance_to_axis_segment(point)
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::Capsule;
+    use crate::shapes::Shape;
+
+    #[test]
+    fn capsule_contains_points_in_hemispherical_caps() {
+        let capsule = Capsule::new(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 4.0), 1.0, 0);
+
+        assert!(capsule.contains_point(&vec3(0.0, 0.0, 2.0)));
+        assert!(capsule.contains_point(&vec3(0.0, 0.0, -0.9)));
+        assert!(capsule.contains_point(&vec3(0.0, 0.0, 4.9)));
+        assert!(!capsule.contains_point(&vec3(0.7, 0.0, -0.8)));
+        assert!(!capsule.contains_point(&vec3(1.1, 0.0, 2.0)));
+    }
+
+    #[test]
+    fn capsule_bounding_box_includes_caps() {
+        let capsule = Capsule::new(vec3(1.0, 2.0, 3.0), vec3(4.0, 2.0, 3.0), 0.5, 0);
+        let bounding_box = capsule.create_bounding_box();
+
+        assert!((bounding_box.min - vec3(0.5, 1.5, 2.5)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(4.5, 2.5, 3.5)).magnitude() < 1e-9);
+    }
+}
@@ -0,0 +1,177 @@
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+use crate::shapes::shape::Shape;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompositeOperation {
+    Union,
+    Intersection,
+    /// The first child with all the other children cut out of it
+    Difference,
+}
+
+/// Constructive solid geometry combination of other shapes. The object ids of the children are ignored
+pub struct Composite {
+    operation: CompositeOperation,
+    children: Vec<Box<dyn Shape>>,
+    object_id: u32,
+}
+
+impl Composite {
+    pub fn new(
+        operation: CompositeOperation,
+        children: Vec<Box<dyn Shape>>,
+        object_id: u32,
+    ) -> Self {
+        Composite {
+            operation,
+            children,
+            object_id,
+        }
+    }
+}
+
+impl Shape for Composite {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        match self.operation {
+            CompositeOperation::Union => self.children.iter().any(|c| c.contains_point(point)),
+            CompositeOperation::Intersection => {
+                !self.children.is_empty() && self.children.iter().all(|c| c.contains_point(point))
+            }
+            CompositeOperation::Difference => match self.children.split_first() {
+                Some((first, rest)) => {
+                    first.contains_point(point) && !rest.iter().any(|c| c.contains_point(point))
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let mut child_boxes = self.children.iter().map(|c| c.create_bounding_box());
+
+        match self.operation {
+            CompositeOperation::Union => {
+                let mut bounding_box: BoundingBox = Default::default();
+                child_boxes.for_each(|child_box| bounding_box.add_box(&child_box));
+                bounding_box
+            }
+            CompositeOperation::Intersection => child_boxes
+                .reduce(|a, b| a.intersection(&b))
+                .unwrap_or_default(),
+            CompositeOperation::Difference => child_boxes.next().unwrap_or_default(),
+        }
+    }
+
+    fn create_bounding_boxes(&self) -> Vec<BoundingBox> {
+        match self.operation {
+            CompositeOperation::Union => self
+                .children
+                .iter()
+                .flat_map(|c| c.create_bounding_boxes())
+                .collect(),
+            CompositeOperation::Intersection => vec![self.create_bounding_box()],
+            CompositeOperation::Difference => self
+                .children
+                .first()
+                .map(|c| c.create_bounding_boxes())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Upper bound on the volume, which is exact for non-overlapping unions
+    fn get_volume(&self) -> f64 {
+        let mut child_volumes = self.children.iter().map(|c| c.get_volume());
+
+        match self.operation {
+            CompositeOperation::Union => child_volumes.sum(),
+            CompositeOperation::Intersection => child_volumes.fold(f64::INFINITY, f64::min),
+            CompositeOperation::Difference => child_volumes.next().unwrap_or(0.0),
+        }
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        let mut child_distances = self
+            .children
+            .iter()
+            .map(|c| c.get_distance_to_center(point));
+
+        match self.operation {
+            CompositeOperation::Union => child_distances.fold(f64::INFINITY, f64::min),
+            CompositeOperation::Intersection => child_distances.fold(0.0, f64::max),
+            CompositeOperation::Difference => child_distances.next().unwrap_or(f64::INFINITY),
+        }
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{vec3, DMat4};
+
+    use super::{Composite, CompositeOperation};
+    use crate::shapes::{Cylinder, OrientedBox, Shape, Sphere};
+
+    fn create_box_and_cylinder() -> Vec<Box<dyn Shape>> {
+        vec![
+            Box::new(OrientedBox::new(DMat4::identity(), 0)),
+            Box::new(Cylinder::new(
+                vec3(0.0, 0.0, -2.0),
+                vec3(0.0, 0.0, 2.0),
+                0.5,
+                0,
+            )),
+        ]
+    }
+
+    #[test]
+    fn box_minus_cylinder_excludes_hole() {
+        let composite =
+            Composite::new(CompositeOperation::Difference, create_box_and_cylinder(), 3);
+
+        assert!(!composite.contains_point(&vec3(0.0, 0.0, 0.0)));
+        assert!(composite.contains_point(&vec3(0.8, 0.0, 0.0)));
+        assert!(!composite.contains_point(&vec3(0.0, 0.0, 1.5)));
+        assert_eq!(composite.get_object_id(), 3);
+    }
+
+    #[test]
+    fn intersection_contains_only_points_in_all_children() {
+        let composite = Composite::new(
+            CompositeOperation::Intersection,
+            create_box_and_cylinder(),
+            0,
+        );
+
+        assert!(composite.contains_point(&vec3(0.0, 0.0, 0.9)));
+        assert!(!composite.contains_point(&vec3(0.0, 0.0, 1.5)));
+        assert!(!composite.contains_point(&vec3(0.8, 0.0, 0.0)));
+
+        let bounding_box = composite.create_bounding_box();
+        assert!((bounding_box.min - vec3(-0.5, -0.5, -1.0)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(0.5, 0.5, 1.0)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn nested_union_has_bounding_box_per_leaf_shape() {
+        let inner: Box<dyn Shape> = Box::new(Composite::new(
+            CompositeOperation::Union,
+            create_box_and_cylinder(),
+            0,
+        ));
+        let composite = Composite::new(
+            CompositeOperation::Union,
+            vec![inner, Box::new(Sphere::new(vec3(5.0, 0.0, 0.0), 1.0, 0))],
+            0,
+        );
+
+        assert!(composite.contains_point(&vec3(0.0, 0.0, 1.5)));
+        assert!(composite.contains_point(&vec3(5.5, 0.0, 0.0)));
+        assert!(!composite.contains_point(&vec3(3.0, 0.0, 0.0)));
+        assert_eq!(composite.create_bounding_boxes().len(), 3);
+    }
+}
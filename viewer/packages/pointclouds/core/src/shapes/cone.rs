@@ -0,0 +1,148 @@
+use std::f64::consts::PI;
+
+use nalgebra_glm::{dot, DVec3};
+
+use crate::linalg::BoundingBox;
+use crate::shapes::shape::Shape;
+
+/// Truncated cone (frustum) with separate radii at its two end caps.
+/// A proper cone is obtained by setting one of the radii to zero
+pub struct Cone {
+    center_a: DVec3,
+    center_b: DVec3,
+    radius_a: f64,
+    radius_b: f64,
+    object_id: u32,
+}
+
+impl Cone {
+    pub fn new(
+        center_a: DVec3,
+        center_b: DVec3,
+        radius_a: f64,
+        radius_b: f64,
+        object_id: u32,
+    ) -> Self {
+        Cone {
+            center_a,
+            center_b,
+            radius_a,
+            radius_b,
+            object_id,
+        }
+    }
+}
+
+impl Cone {
+    /// Returns the distance along the axis from `center_a`, and the distance to the axis
+    fn get_axis_coordinates(&self, point: &DVec3) -> (f64, f64) {
+        let axis = (self.center_b - self.center_a).normalize();
+
+        let dist_along_axis = dot(&(point - self.center_a), &axis);
+        let axis_point = self.center_a + axis * dist_along_axis;
+
+        (dist_along_axis, (point - axis_point).magnitude())
+    }
+}
+
+impl Shape for Cone {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        let height = (self.center_b - self.center_a).magnitude();
+        let (dist_along_axis, dist_to_axis) = self.get_axis_coordinates(point);
+
+        if dist_along_axis <= 0.0 || dist_along_axis >= height {
+            return false;
+        }
+
+        let t = dist_along_axis / height;
+        let radius_at_point = self.radius_a + (self.radius_b - self.radius_a) * t;
+
+        dist_to_axis < radius_at_point
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let axis = (self.center_b - self.center_a).normalize();
+
+        let mut bounding_box =
+            BoundingBox::get_disc_bounding_box(&self.center_a, &axis, self.radius_a);
+        bounding_box.add_box(&BoundingBox::get_disc_bounding_box(
+            &self.center_b,
+            &axis,
+            self.radius_b,
+        ));
+
+        bounding_box
+    }
+
+    fn get_volume(&self) -> f64 {
+        let height = (self.center_b - self.center_a).magnitude();
+        PI * height / 3.0
+            * (self.radius_a * self.radius_a
+                + self.radius_a * self.radius_b
+                + self.radius_b * self.radius_b)
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        self.get_axis_coordinates(point).1
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::Cone;
+    use crate::shapes::Shape;
+
+    #[test]
+    fn cone_radius_varies_linearly_along_axis() {
+        let cone = Cone::new(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 2.0), 1.0, 0.0, 0);
+
+        assert!(cone.contains_point(&vec3(0.0, 0.0, 1.0)));
+        assert!(cone.contains_point(&vec3(0.9, 0.0, 0.05)));
+        assert!(cone.contains_point(&vec3(0.0, 0.45, 1.0)));
+        assert!(!cone.contains_point(&vec3(0.0, 0.55, 1.0)));
+        assert!(!cone.contains_point(&vec3(0.1, 0.1, 1.95)));
+    }
+
+    #[test]
+    fn cone_does_not_contain_points_beyond_caps() {
+        let cone = Cone::new(vec3(1.0, 1.0, 1.0), vec3(4.0, 1.0, 1.0), 0.5, 1.5, 0);
+
+        assert!(!cone.contains_point(&vec3(0.9, 1.0, 1.0)));
+        assert!(!cone.contains_point(&vec3(4.1, 1.0, 1.0)));
+        assert!(cone.contains_point(&vec3(3.9, 2.3, 1.0)));
+    }
+
+    #[test]
+    fn frustum_bounding_box_is_tight_for_axis_aligned_frustum() {
+        let cone = Cone::new(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 3.0), 2.0, 1.0, 0);
+        let bounding_box = cone.create_bounding_box();
+
+        assert!((bounding_box.min - vec3(-2.0, -2.0, 0.0)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(2.0, 2.0, 3.0)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn diagonal_frustum_bounding_box_contains_rim_points() {
+        let center_a = vec3(2.0, -1.0, 5.0);
+        let center_b = vec3(-3.0, 4.0, 1.0);
+        let cone = Cone::new(center_a, center_b, 0.3, 1.2, 0);
+        let bounding_box = cone.create_bounding_box();
+
+        let axis = (center_b - center_a).normalize();
+        let perp_0 = axis.cross(&vec3(1.0, 0.0, 0.0)).normalize();
+        let perp_1 = axis.cross(&perp_0);
+
+        for i in 0..16 {
+            let angle = i as f64 * std::f64::consts::PI / 8.0;
+            let direction = perp_0 * angle.cos() + perp_1 * angle.sin();
+            assert!(bounding_box.contains_point(&(center_a + direction * 0.3)));
+            assert!(bounding_box.contains_point(&(center_b + direction * 1.2)));
+        }
+    }
+}
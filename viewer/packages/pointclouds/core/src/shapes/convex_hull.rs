@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+
+use nalgebra_glm::{dot, DVec3};
+
+use crate::shapes::convex_polyhedron::HalfSpace;
+
+const RELATIVE_EPSILON: f64 = 1e-9;
+const COPLANAR_NORMAL_EPSILON: f64 = 1e-9;
+
+/// Distance below which points count as coplanar, relative to the size of the coordinates
+pub fn get_epsilon(points: &[DVec3]) -> f64 {
+    let max_coordinate = points
+        .iter()
+        .map(|point| point.abs().max())
+        .fold(1.0, f64::max);
+    RELATIVE_EPSILON * max_coordinate
+}
+
+/// Triangle of the hull, with vertices ordered counter-clockwise seen from outside
+struct HullFace {
+    vertices: [usize; 3],
+    normal: DVec3,
+    offset: f64,
+    /// Points above the face that are not part of the hull yet
+    outside_points: Vec<usize>,
+    is_removed: bool,
+}
+
+impl HullFace {
+    fn new(points: &[DVec3], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|i| points[i]);
+        let normal = (b - a).cross(&(c - a)).normalize();
+
+        HullFace {
+            vertices,
+            normal,
+            offset: dot(&normal, &a),
+            outside_points: vec![],
+            is_removed: false,
+        }
+    }
+
+    fn signed_distance(&self, point: &DVec3) -> f64 {
+        dot(&self.normal, point) - self.offset
+    }
+
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.vertices;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Triangulated convex hull of a point set, built incrementally by adding the farthest
+/// outside point of a face and replacing all faces it can see. Each face keeps the list of
+/// points outside it (its conflict list), so points are only tested against nearby faces
+pub struct ConvexHull<'a> {
+    points: &'a [DVec3],
+    faces: Vec<HullFace>,
+    /// Face index for each directed edge, used to find the neighbor across an edge
+    edge_faces: HashMap<(usize, usize), usize>,
+    epsilon: f64,
+}
+
+impl<'a> ConvexHull<'a> {
+    /// Returns `None` if the points do not span a volume
+    pub fn try_new(points: &'a [DVec3], epsilon: f64) -> Option<Self> {
+        let simplex = find_initial_simplex(points, epsilon)?;
+        let mut hull = ConvexHull {
+            points,
+            faces: vec![],
+            edge_faces: HashMap::new(),
+            epsilon,
+        };
+
+        let [a, b, c, d] = simplex;
+        let initial_faces =
+            [[a, b, c], [b, a, d], [c, b, d], [a, c, d]].map(|vertices| hull.add_face(vertices));
+
+        let remaining_points = (0..points.len()).filter(|i| !simplex.contains(i));
+        hull.assign_outside_points(remaining_points, &initial_faces);
+
+        let mut pending_faces = initial_faces.to_vec();
+        while let Some(face_index) = pending_faces.pop() {
+            if !hull.faces[face_index].is_removed {
+                pending_faces.extend(hull.add_farthest_outside_point(face_index));
+            }
+        }
+
+        hull.faces.retain(|face| !face.is_removed);
+        hull.edge_faces = hull
+            .faces
+            .iter()
+            .enumerate()
+            .flat_map(|(i, face)| face.edges().map(|edge| (edge, i)))
+            .collect();
+
+        Some(hull)
+    }
+
+    fn add_face(&mut self, vertices: [usize; 3]) -> usize {
+        let face = HullFace::new(self.points, vertices);
+        let face_index = self.faces.len();
+        for edge in face.edges() {
+            self.edge_faces.insert(edge, face_index);
+        }
+        self.faces.push(face);
+        face_index
+    }
+
+    fn assign_outside_points(&mut self, points: impl Iterator<Item = usize>, faces: &[usize]) {
+        for point in points {
+            let position = self.points[point];
+            if let Some(&face_index) = faces
+                .iter()
+                .find(|&&f| self.faces[f].signed_distance(&position) > self.epsilon)
+            {
+                self.faces[face_index].outside_points.push(point);
+            }
+        }
+    }
+
+    /// Adds the outside point farthest from the face to the hull, and returns the new faces
+    fn add_farthest_outside_point(&mut self, face_index: usize) -> Vec<usize> {
+        let face = &self.faces[face_index];
+        let Some(&apex) = face.outside_points.iter().max_by(|&&a, &&b| {
+            face.signed_distance(&self.points[a])
+                .total_cmp(&face.signed_distance(&self.points[b]))
+        }) else {
+            return vec![];
+        };
+        let apex_position = self.points[apex];
+
+        // Collect the connected region of faces that can see the apex
+        let mut visible_faces = vec![face_index];
+        let mut is_visible = HashMap::from([(face_index, true)]);
+        let mut to_visit = vec![face_index];
+        while let Some(visible_face) = to_visit.pop() {
+            for (a, b) in self.faces[visible_face].edges() {
+                let neighbor = self.edge_faces[&(b, a)];
+                is_visible.entry(neighbor).or_insert_with(|| {
+                    let can_see_apex =
+                        self.faces[neighbor].signed_distance(&apex_position) > self.epsilon;
+                    if can_see_apex {
+                        visible_faces.push(neighbor);
+                        to_visit.push(neighbor);
+                    }
+                    can_see_apex
+                });
+            }
+        }
+
+        let horizon: Vec<(usize, usize)> = visible_faces
+            .iter()
+            .flat_map(|&f| self.faces[f].edges())
+            .filter(|&(a, b)| !is_visible[&self.edge_faces[&(b, a)]])
+            .collect();
+
+        let mut orphaned_points = vec![];
+        for &f in &visible_faces {
+            let face = &mut self.faces[f];
+            face.is_removed = true;
+            orphaned_points.append(&mut face.outside_points);
+            for edge in face.edges() {
+                self.edge_faces.remove(&edge);
+            }
+        }
+
+        let new_faces: Vec<usize> = horizon
+            .iter()
+            .map(|&(a, b)| self.add_face([a, b, apex]))
+            .collect();
+        self.assign_outside_points(
+            orphaned_points.into_iter().filter(|&point| point != apex),
+            &new_faces,
+        );
+
+        new_faces
+    }
+
+    /// Indices of the points that are corners of the hull
+    pub fn get_vertex_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.faces.iter().flat_map(|face| face.vertices).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// One half-space per plane of the hull, merging adjacent coplanar triangles
+    pub fn get_half_spaces(&self) -> Vec<HalfSpace> {
+        // Union-find over the faces, joining neighbors that lie in the same plane
+        let mut parents: Vec<usize> = (0..self.faces.len()).collect();
+        fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+
+        for (i, face) in self.faces.iter().enumerate() {
+            for (a, b) in face.edges() {
+                let neighbor_index = self.edge_faces[&(b, a)];
+                let neighbor = &self.faces[neighbor_index];
+                let is_coplanar = dot(&face.normal, &neighbor.normal)
+                    > 1.0 - COPLANAR_NORMAL_EPSILON
+                    && (face.offset - neighbor.offset).abs() <= self.epsilon;
+                if is_coplanar {
+                    let root = find_root(&mut parents, i);
+                    let neighbor_root = find_root(&mut parents, neighbor_index);
+                    parents[neighbor_root] = root;
+                }
+            }
+        }
+
+        let mut half_spaces: HashMap<usize, HalfSpace> = HashMap::new();
+        for (i, face) in self.faces.iter().enumerate() {
+            let root = find_root(&mut parents, i);
+            let normal = self.faces[root].normal;
+            let half_space = half_spaces.entry(root).or_insert(HalfSpace {
+                normal,
+                offset: f64::MIN,
+            });
+
+            // Place the plane so that all vertices of the merged faces are inside
+            for vertex in face.vertices {
+                half_space.offset = half_space.offset.max(dot(&normal, &self.points[vertex]));
+            }
+        }
+
+        let mut roots: Vec<usize> = half_spaces.keys().copied().collect();
+        roots.sort_unstable();
+        roots.iter().map(|root| half_spaces[root]).collect()
+    }
+
+    /// Width of the hull along each unit direction. The extreme vertices are found by walking
+    /// the edges of the hull from those of the previous direction, since a hull vertex with no
+    /// neighbor farther along a direction is the farthest of all points
+    pub fn get_widths(&self, directions: &[DVec3]) -> Vec<f64> {
+        let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (a, b) in self.faces.iter().flat_map(|face| face.edges()) {
+            neighbors.entry(a).or_default().push(b);
+        }
+
+        let find_farthest = |direction: &DVec3, mut vertex: usize| loop {
+            let distance = dot(direction, &self.points[vertex]);
+            match neighbors[&vertex]
+                .iter()
+                .find(|&&neighbor| dot(direction, &self.points[neighbor]) > distance)
+            {
+                Some(&neighbor) => vertex = neighbor,
+                None => return vertex,
+            }
+        };
+
+        let mut max_vertex = self.faces[0].vertices[0];
+        let mut min_vertex = max_vertex;
+        directions
+            .iter()
+            .map(|direction| {
+                max_vertex = find_farthest(direction, max_vertex);
+                min_vertex = find_farthest(&-direction, min_vertex);
+                dot(
+                    direction,
+                    &(self.points[max_vertex] - self.points[min_vertex]),
+                )
+            })
+            .collect()
+    }
+
+    /// Sums the signed volumes of the tetrahedra from a hull vertex to each face
+    pub fn get_volume(&self) -> f64 {
+        let reference = self.points[self.faces[0].vertices[0]];
+
+        self.faces
+            .iter()
+            .map(|face| {
+                let [a, b, c] = face.vertices.map(|i| self.points[i] - reference);
+                dot(&a, &b.cross(&c)) / 6.0
+            })
+            .sum()
+    }
+}
+
+/// Finds four points spanning a tetrahedron with a large volume, starting from the pair of
+/// axis-extreme points farthest apart
+fn find_initial_simplex(points: &[DVec3], epsilon: f64) -> Option<[usize; 4]> {
+    let farthest_from = |distance: &dyn Fn(&DVec3) -> f64| {
+        (0..points.len())
+            .map(|i| (i, distance(&points[i])))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|&(_, distance)| distance > epsilon)
+            .map(|(i, _)| i)
+    };
+
+    let extremes: Vec<usize> = (0..3)
+        .flat_map(|dim| {
+            let by_coordinate = |a: &&DVec3, b: &&DVec3| a[dim].total_cmp(&b[dim]);
+            let min = points
+                .iter()
+                .enumerate()
+                .min_by(|a, b| by_coordinate(&a.1, &b.1));
+            let max = points
+                .iter()
+                .enumerate()
+                .max_by(|a, b| by_coordinate(&a.1, &b.1));
+            [min, max].into_iter().flatten().map(|(i, _)| i)
+        })
+        .collect();
+    let (a, b) = extremes
+        .iter()
+        .flat_map(|&i| extremes.iter().map(move |&j| (i, j)))
+        .max_by(|&(a, b), &(c, d)| {
+            (points[a] - points[b])
+                .magnitude()
+                .total_cmp(&(points[c] - points[d]).magnitude())
+        })?;
+    if (points[a] - points[b]).magnitude() <= epsilon {
+        return None;
+    }
+
+    let line_direction = (points[b] - points[a]).normalize();
+    let c = farthest_from(&|p| {
+        let relative = p - points[a];
+        (relative - line_direction * dot(&relative, &line_direction)).magnitude()
+    })?;
+
+    let normal = (points[b] - points[a])
+        .cross(&(points[c] - points[a]))
+        .normalize();
+    let d = farthest_from(&|p| dot(&normal, &(p - points[a])).abs())?;
+
+    // Order the base so that it faces away from the fourth point
+    if dot(&normal, &(points[d] - points[a])) > 0.0 {
+        Some([a, c, b, d])
+    } else {
+        Some([a, b, c, d])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{vec3, DVec3};
+
+    use super::ConvexHull;
+
+    fn create_sphere_points(count: usize) -> Vec<DVec3> {
+        // Fibonacci sphere, so that all points are corners of the hull
+        let golden_angle = std::f64::consts::PI * (3.0 - 5.0f64.sqrt());
+        (0..count)
+            .map(|i| {
+                let z = 1.0 - 2.0 * (i as f64 + 0.5) / count as f64;
+                let radius = (1.0 - z * z).sqrt();
+                let angle = golden_angle * i as f64;
+                vec3(radius * angle.cos(), radius * angle.sin(), z)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_points_on_a_sphere_are_hull_vertices() {
+        let points = create_sphere_points(500);
+        let hull = ConvexHull::try_new(&points, 1e-9).unwrap();
+
+        assert_eq!(hull.get_vertex_indices().len(), 500);
+        assert_eq!(hull.get_half_spaces().len(), 2 * 500 - 4);
+        for half_space in hull.get_half_spaces() {
+            assert!(points
+                .iter()
+                .all(|p| nalgebra_glm::dot(&half_space.normal, p) - half_space.offset < 1e-9));
+        }
+    }
+
+    #[test]
+    fn coplanar_triangles_are_merged_into_one_half_space() {
+        let mut points = vec![];
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    points.push(vec3(x as f64, y as f64, z as f64));
+                }
+            }
+        }
+        let hull = ConvexHull::try_new(&points, 1e-9).unwrap();
+
+        assert_eq!(hull.get_half_spaces().len(), 6);
+        assert_eq!(hull.get_vertex_indices().len(), 8);
+        assert!((hull.get_volume() - 27.0).abs() < 1e-9);
+
+        let widths = hull.get_widths(&[vec3(0.0, 0.0, -1.0), vec3(1.0, 1.0, 0.0).normalize()]);
+        assert!((widths[0] - 3.0).abs() < 1e-9);
+        assert!((widths[1] - 3.0 * 2.0f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_point_sets_have_no_hull() {
+        let points = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+        ];
+
+        assert!(ConvexHull::try_new(&points, 1e-9).is_none());
+        assert!(ConvexHull::try_new(&[], 1e-9).is_none());
+    }
+}
@@ -0,0 +1,430 @@
+use nalgebra_glm::{dot, vec3, DVec3};
+
+use crate::linalg::BoundingBox;
+use crate::shapes::convex_hull::{get_epsilon, ConvexHull};
+use crate::shapes::linear_program::{self, Constraint};
+use crate::shapes::shape::Shape;
+use crate::shapes::tolerance::Tolerance;
+
+const RELATIVE_EPSILON: f64 = 1e-9;
+const BOX_GROWTH_FACTOR: f64 = 16.0;
+const MAX_BOX_GROWTH_STEPS: usize = 8;
+
+/// Half-space `{ p : dot(normal, p) <= offset }`, with `normal` pointing out of the volume
+#[derive(Clone, Copy, Debug)]
+pub struct HalfSpace {
+    pub normal: DVec3,
+    pub offset: f64,
+}
+
+impl HalfSpace {
+    fn signed_distance(&self, point: &DVec3) -> f64 {
+        dot(&self.normal, point) - self.offset
+    }
+}
+
+/// Convex volume given by the intersection of a set of half-spaces
+pub struct ConvexPolyhedron {
+    half_spaces: Vec<HalfSpace>,
+    vertices: Vec<DVec3>,
+    volume: f64,
+    object_id: u32,
+}
+
+impl ConvexPolyhedron {
+    pub fn from_half_spaces(half_spaces: &[HalfSpace], object_id: u32) -> Result<Self, String> {
+        let half_spaces = half_spaces
+            .iter()
+            .map(|half_space| {
+                let length = half_space.normal.magnitude();
+                if length > 0.0 && length.is_finite() {
+                    Ok(HalfSpace {
+                        normal: half_space.normal / length,
+                        offset: half_space.offset / length,
+                    })
+                } else {
+                    Err("Convex polyhedron has degenerate plane normal".to_string())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !normals_enclose_origin(&half_spaces) {
+            return Err("Convex polyhedron half-spaces do not bound a closed volume".to_string());
+        }
+
+        let empty_error = || "Convex polyhedron half-spaces have empty intersection".to_string();
+        let center = find_interior_point(&half_spaces).ok_or_else(empty_error)?;
+
+        // Relative to an interior point, each half-space maps to a dual point. The faces of
+        // the dual points' hull correspond to the vertices of the intersection, and the dual
+        // points on the hull to the half-spaces that are not redundant
+        let dual_points: Vec<DVec3> = half_spaces
+            .iter()
+            .map(|half_space| half_space.normal / -half_space.signed_distance(&center))
+            .collect();
+        let dual_hull =
+            ConvexHull::try_new(&dual_points, get_epsilon(&dual_points)).ok_or_else(empty_error)?;
+
+        let vertices: Vec<DVec3> = dual_hull
+            .get_half_spaces()
+            .iter()
+            .map(|dual_face| center + dual_face.normal / dual_face.offset)
+            .collect();
+        let volume = ConvexHull::try_new(&vertices, get_epsilon(&vertices))
+            .ok_or_else(empty_error)?
+            .get_volume();
+
+        Ok(ConvexPolyhedron {
+            half_spaces: dual_hull
+                .get_vertex_indices()
+                .iter()
+                .map(|&i| half_spaces[i])
+                .collect(),
+            vertices,
+            volume,
+            object_id,
+        })
+    }
+
+    /// Creates the convex hull of the given vertices
+    pub fn from_vertices(vertices: &[DVec3], object_id: u32) -> Result<Self, String> {
+        let hull = ConvexHull::try_new(vertices, get_epsilon(vertices))
+            .ok_or_else(|| "Convex polyhedron vertices do not span a volume".to_string())?;
+
+        Ok(ConvexPolyhedron {
+            half_spaces: hull.get_half_spaces(),
+            vertices: hull
+                .get_vertex_indices()
+                .iter()
+                .map(|&i| vertices[i])
+                .collect(),
+            volume: hull.get_volume(),
+            object_id,
+        })
+    }
+
+    fn get_center(&self) -> DVec3 {
+        self.vertices.iter().sum::<DVec3>() / self.vertices.len() as f64
+    }
+
+    /// Moves each plane out by the margin for the polyhedron's half-width along its normal, so
+    /// that a box grows like an oriented box, and thin shapes still grow sideways
+    pub fn inflated(self, tolerance: &Tolerance) -> Result<Self, String> {
+        let hull = ConvexHull::try_new(&self.vertices, get_epsilon(&self.vertices))
+            .ok_or_else(|| "Convex polyhedron vertices do not span a volume".to_string())?;
+        let normals: Vec<DVec3> = self
+            .half_spaces
+            .iter()
+            .map(|half_space| half_space.normal)
+            .collect();
+
+        let half_spaces: Vec<HalfSpace> = self
+            .half_spaces
+            .iter()
+            .zip(hull.get_widths(&normals))
+            .map(|(half_space, width)| HalfSpace {
+                normal: half_space.normal,
+                offset: half_space.offset + tolerance.get_margin(width / 2.0),
+            })
+            .collect();
+
+        ConvexPolyhedron::from_half_spaces(&half_spaces, self.object_id)
+    }
+}
+
+/// The intersection of the half-spaces is bounded exactly when the origin
+/// is strictly inside the convex hull of the normals
+fn normals_enclose_origin(half_spaces: &[HalfSpace]) -> bool {
+    let normals: Vec<DVec3> = half_spaces
+        .iter()
+        .map(|half_space| half_space.normal)
+        .collect();
+
+    ConvexHull::try_new(&normals, RELATIVE_EPSILON).is_some_and(|hull| {
+        hull.get_half_spaces()
+            .iter()
+            .all(|half_space| half_space.offset > RELATIVE_EPSILON)
+    })
+}
+
+/// Finds the point farthest inside all half-spaces. The search is limited to boxes of growing
+/// size, since bounded intersections can still lie far from the origin. Returns `None` if the
+/// intersection has no volume
+fn find_interior_point(half_spaces: &[HalfSpace]) -> Option<DVec3> {
+    // Variables are the point and its margin: dot(normal, point) + margin <= offset
+    let constraints: Vec<Constraint> = half_spaces
+        .iter()
+        .map(|half_space| Constraint {
+            normal: vec![
+                half_space.normal.x,
+                half_space.normal.y,
+                half_space.normal.z,
+                1.0,
+            ],
+            offset: half_space.offset,
+        })
+        .collect();
+
+    let mut half_size = 2.0
+        * half_spaces
+            .iter()
+            .map(|half_space| half_space.offset.abs())
+            .fold(1.0, f64::max);
+
+    for _ in 0..MAX_BOX_GROWTH_STEPS {
+        let epsilon = RELATIVE_EPSILON * half_size;
+        let [x, y, z, margin] = linear_program::maximize(
+            &[0.0, 0.0, 0.0, 1.0],
+            &constraints,
+            &[-half_size; 4],
+            &[half_size; 4],
+            epsilon,
+        )?[..] else {
+            return None;
+        };
+
+        let point = vec3(x, y, z);
+        if margin > epsilon {
+            return Some(point);
+        }
+
+        // Without a positive margin inside the box, the intersection is either empty or outside
+        if point.abs().max() < half_size * (1.0 - RELATIVE_EPSILON) {
+            return None;
+        }
+        half_size *= BOX_GROWTH_FACTOR;
+    }
+
+    None
+}
+
+impl Shape for ConvexPolyhedron {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        self.half_spaces
+            .iter()
+            .all(|half_space| half_space.signed_distance(point) < 0.0)
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        self.vertices.iter().copied().collect()
+    }
+
+    fn get_volume(&self) -> f64 {
+        self.volume
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.get_center()).magnitude()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{vec3, DVec3};
+
+    use super::{ConvexPolyhedron, HalfSpace};
+    use crate::shapes::{Shape, Tolerance};
+
+    fn create_unit_cube_half_spaces() -> Vec<HalfSpace> {
+        (0..3)
+            .flat_map(|dim| {
+                [-1.0, 1.0].map(|sign| {
+                    let mut normal = DVec3::zeros();
+                    normal[dim] = sign;
+                    HalfSpace {
+                        normal,
+                        offset: 1.0,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cube_from_half_spaces_contains_inside_points_only() {
+        let cube = ConvexPolyhedron::from_half_spaces(&create_unit_cube_half_spaces(), 0).unwrap();
+
+        assert!(cube.contains_point(&vec3(0.0, 0.0, 0.0)));
+        assert!(cube.contains_point(&vec3(0.9, -0.9, 0.9)));
+        assert!(!cube.contains_point(&vec3(1.1, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn bounding_box_of_slanted_polyhedron_is_exact() {
+        let mut half_spaces = create_unit_cube_half_spaces();
+        half_spaces.push(HalfSpace {
+            normal: vec3(1.0, 1.0, 1.0),
+            offset: 0.0,
+        });
+
+        let polyhedron = ConvexPolyhedron::from_half_spaces(&half_spaces, 0).unwrap();
+        let bounding_box = polyhedron.create_bounding_box();
+
+        assert!((bounding_box.min - vec3(-1.0, -1.0, -1.0)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(1.0, 1.0, 1.0)).magnitude() < 1e-9);
+        assert!(!polyhedron.contains_point(&vec3(0.5, 0.5, 0.5)));
+        assert!(polyhedron.contains_point(&vec3(-0.5, 0.5, -0.5)));
+    }
+
+    #[test]
+    fn redundant_half_spaces_are_dropped() {
+        let mut half_spaces = create_unit_cube_half_spaces();
+        half_spaces.extend(create_unit_cube_half_spaces());
+        half_spaces.push(HalfSpace {
+            normal: vec3(1.0, 1.0, 1.0),
+            offset: 10.0,
+        });
+
+        let cube = ConvexPolyhedron::from_half_spaces(&half_spaces, 0).unwrap();
+
+        assert_eq!(cube.half_spaces.len(), 6);
+        assert_eq!(cube.vertices.len(), 8);
+    }
+
+    #[test]
+    fn intersection_far_from_the_origin_is_found() {
+        // Two nearly parallel planes meeting around x = 2000, far outside the initial box
+        let half_spaces = [
+            (vec3(-0.001, 1.0, 0.0), -1.0),
+            (vec3(0.002, -1.0, 0.0), 3.0),
+            (vec3(0.0, -1.0, 0.0), 0.0),
+            (vec3(0.0, 0.0, 1.0), 1.0),
+            (vec3(0.0, 0.0, -1.0), 1.0),
+        ]
+        .map(|(normal, offset)| HalfSpace { normal, offset });
+
+        let polyhedron = ConvexPolyhedron::from_half_spaces(&half_spaces, 0).unwrap();
+        let bounding_box = polyhedron.create_bounding_box();
+
+        assert!((bounding_box.min - vec3(1000.0, 0.0, -1.0)).magnitude() < 1e-6);
+        assert!((bounding_box.max - vec3(2000.0, 1.0, 1.0)).magnitude() < 1e-6);
+        assert!(polyhedron.contains_point(&vec3(1800.0, 0.7, 0.0)));
+        assert!(!polyhedron.contains_point(&vec3(1800.0, 0.5, 0.0)));
+    }
+
+    #[test]
+    fn disjoint_half_spaces_are_rejected() {
+        let mut half_spaces = create_unit_cube_half_spaces();
+        half_spaces.push(HalfSpace {
+            normal: vec3(-1.0, 0.0, 0.0),
+            offset: -2.0,
+        });
+
+        assert!(ConvexPolyhedron::from_half_spaces(&half_spaces, 0).is_err());
+    }
+
+    #[test]
+    fn large_inputs_are_hulled_and_intersected() {
+        let golden_angle = std::f64::consts::PI * (3.0 - 5.0f64.sqrt());
+        let directions: Vec<DVec3> = (0..1000)
+            .map(|i| {
+                let z = 1.0 - 2.0 * (i as f64 + 0.5) / 1000.0;
+                let radius = (1.0 - z * z).sqrt();
+                let angle = golden_angle * i as f64;
+                vec3(radius * angle.cos(), radius * angle.sin(), z)
+            })
+            .collect();
+
+        let hull = ConvexPolyhedron::from_vertices(&directions, 0).unwrap();
+        assert_eq!(hull.vertices.len(), 1000);
+        assert!((hull.get_volume() - 4.0 / 3.0 * std::f64::consts::PI).abs() < 0.05);
+
+        let half_spaces: Vec<HalfSpace> = directions
+            .iter()
+            .map(|&normal| HalfSpace {
+                normal,
+                offset: 1.0,
+            })
+            .collect();
+        let circumscribed = ConvexPolyhedron::from_half_spaces(&half_spaces, 0).unwrap();
+        assert_eq!(circumscribed.half_spaces.len(), 1000);
+        assert!(circumscribed.contains_point(&vec3(0.0, 0.0, 0.999)));
+        assert!(!circumscribed.contains_point(&vec3(0.0, 0.0, 1.01)));
+    }
+
+    #[test]
+    fn unbounded_half_spaces_are_rejected() {
+        let mut half_spaces = create_unit_cube_half_spaces();
+        half_spaces.pop();
+
+        assert!(ConvexPolyhedron::from_half_spaces(&half_spaces, 0).is_err());
+    }
+
+    #[test]
+    fn hull_of_tetrahedron_with_interior_point_contains_centroid() {
+        let vertices = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(2.0, 0.0, 0.0),
+            vec3(0.0, 2.0, 0.0),
+            vec3(0.0, 0.0, 2.0),
+            vec3(0.2, 0.2, 0.2),
+        ];
+        let tetrahedron = ConvexPolyhedron::from_vertices(&vertices, 0).unwrap();
+
+        assert!(tetrahedron.contains_point(&vec3(0.5, 0.5, 0.5)));
+        assert!(!tetrahedron.contains_point(&vec3(0.8, 0.8, 0.8)));
+
+        let bounding_box = tetrahedron.create_bounding_box();
+        assert!((bounding_box.min - vec3(0.0, 0.0, 0.0)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(2.0, 2.0, 2.0)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn coplanar_vertices_are_rejected() {
+        let vertices = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+        ];
+
+        assert!(ConvexPolyhedron::from_vertices(&vertices, 0).is_err());
+    }
+
+    #[test]
+    fn inflated_polyhedron_grows_each_face_by_its_half_width() {
+        let vertices = [
+            vec3(-5.0, -0.5, -0.5),
+            vec3(5.0, -0.5, -0.5),
+            vec3(-5.0, 0.5, -0.5),
+            vec3(5.0, 0.5, -0.5),
+            vec3(-5.0, -0.5, 0.5),
+            vec3(5.0, -0.5, 0.5),
+            vec3(-5.0, 0.5, 0.5),
+            vec3(5.0, 0.5, 0.5),
+        ];
+        let tolerance = Tolerance {
+            relative_factor: Some(1.5),
+            absolute_margin: Some(1.0),
+        };
+        let beam = ConvexPolyhedron::from_vertices(&vertices, 0)
+            .unwrap()
+            .inflated(&tolerance)
+            .unwrap();
+
+        let bounding_box = beam.create_bounding_box();
+        assert!((bounding_box.max - vec3(6.0, 0.75, 0.75)).magnitude() < 1e-9);
+        assert!(beam.contains_point(&vec3(5.9, 0.7, 0.0)));
+        assert!(!beam.contains_point(&vec3(5.9, 0.8, 0.0)));
+        assert!((beam.get_volume() - 12.0 * 1.5 * 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn volume_of_cube_and_tetrahedron_is_exact() {
+        let cube = ConvexPolyhedron::from_half_spaces(&create_unit_cube_half_spaces(), 0).unwrap();
+        assert!((cube.get_volume() - 8.0).abs() < 1e-9);
+
+        let vertices = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(3.0, 0.0, 0.0),
+            vec3(0.0, 3.0, 0.0),
+            vec3(0.0, 0.0, 3.0),
+        ];
+        let tetrahedron = ConvexPolyhedron::from_vertices(&vertices, 0).unwrap();
+        assert!((tetrahedron.get_volume() - 4.5).abs() < 1e-9);
+    }
+}
@@ -1,3 +1,5 @@
+use std::f64::consts::PI;
+
 use nalgebra_glm::{dot, mat3_to_mat4, vec3, vec4, DMat3, DMat4, DVec3};
 
 use crate::linalg::BoundingBox;
@@ -7,16 +9,16 @@ pub struct Cylinder {
     center_a: DVec3,
     center_b: DVec3,
     radius: f64,
-    object_id: u16,
+    object_id: u32,
 }
 
 impl Cylinder {
-    pub fn new(center_a: DVec3, center_b: DVec3, radius: f64, object_id: u16) -> Self {
+    pub fn new(center_a: DVec3, center_b: DVec3, radius: f64, object_id: u32) -> Self {
         Cylinder {
-            center_a: center_a,
-            center_b: center_b,
-            radius: radius,
-            object_id: object_id,
+            center_a,
+            center_b,
+            radius,
+            object_id,
         }
     }
 
@@ -24,6 +26,20 @@ impl Cylinder {
         (self.center_a + self.center_b) / 2.0
     }
 
+    /// Returns the signed distance along the axis from the center, and the distance to the axis
+    fn get_axis_coordinates(&self, point: &DVec3) -> (f64, f64) {
+        let center = self.get_center();
+        let axis = (self.center_a - self.center_b).normalize();
+
+        let dist_along_axis = dot(&(point - center), &axis);
+        let dist_vector_along_axis = axis * dist_along_axis;
+        let axis_relative_middle = point - dist_vector_along_axis;
+
+        let dist_to_axis = (axis_relative_middle - center).magnitude();
+
+        (dist_along_axis, dist_to_axis)
+    }
+
     fn get_scaled_orthogonal_basis(&self) -> DMat3 {
         let half_axis_vec = (self.center_a - self.center_b) / 2.0;
         let axis_option_0 = vec3(1.0, 0.0, 0.0);
@@ -56,15 +72,8 @@ fn create_transform_from_axes(scaled_basis: &DMat3, cylinder_center: &DVec3) ->
 
 impl Shape for Cylinder {
     fn contains_point(&self, point: &DVec3) -> bool {
-        let center = self.get_center();
-        let axis = (self.center_a - self.center_b).normalize();
         let half_height = (self.center_a - self.center_b).magnitude() / 2.0;
-
-        let dist_along_axis = dot(&(point - center), &axis);
-        let dist_vector_along_axis = axis * dist_along_axis;
-        let axis_relative_middle = point - dist_vector_along_axis;
-
-        let dist_to_axis = (axis_relative_middle - center).magnitude();
+        let (dist_along_axis, dist_to_axis) = self.get_axis_coordinates(point);
 
         dist_along_axis.abs() < half_height && dist_to_axis < self.radius
     }
@@ -77,7 +86,28 @@ impl Shape for Cylinder {
         BoundingBox::get_transformed_base_cube(&matrix)
     }
 
-    fn get_object_id(&self) -> u16 {
+    fn get_volume(&self) -> f64 {
+        PI * self.radius * self.radius * (self.center_a - self.center_b).magnitude()
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        self.get_axis_coordinates(point).1
+    }
+
+    fn get_signed_distance(&self, point: &DVec3) -> Option<f64> {
+        let half_height = (self.center_a - self.center_b).magnitude() / 2.0;
+        let (dist_along_axis, dist_to_axis) = self.get_axis_coordinates(point);
+
+        let radial_distance = dist_to_axis - self.radius;
+        let axial_distance = dist_along_axis.abs() - half_height;
+
+        let outside_distance = radial_distance.max(0.0).hypot(axial_distance.max(0.0));
+        let inside_distance = radial_distance.max(axial_distance).min(0.0);
+
+        Some(outside_distance + inside_distance)
+    }
+
+    fn get_object_id(&self) -> u32 {
         self.object_id
     }
 }
@@ -85,19 +115,18 @@ impl Shape for Cylinder {
 #[cfg(test)]
 mod tests {
     use nalgebra_glm::vec3;
-    use wasm_bindgen_test::wasm_bindgen_test;
 
     use super::Cylinder;
     use crate::shapes::Shape;
 
-    #[wasm_bindgen_test]
+    #[test]
     fn cylinder_at_origin_contains_middle_point() {
         let cylinder = Cylinder::new(vec3(0.0, -0.5, 0.0), vec3(0.0, 0.5, 0.0), 1.0, 0);
 
         assert!(cylinder.contains_point(&vec3(0.0, 0.0, 0.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn thin_diagonal_cylinder_contains_point_in_middle() {
         let center_a = vec3(213.0, -33.0, 983.12);
         let center_b = vec3(-12.0, 234.0, -10.0);
@@ -111,7 +140,20 @@ mod tests {
         assert!(!cylinder.contains_point(&outside_middle));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
+    fn cylinder_signed_distance_is_negative_inside_and_positive_outside() {
+        let cylinder = Cylinder::new(vec3(0.0, -2.0, 0.0), vec3(0.0, 2.0, 0.0), 1.0, 0);
+
+        let distance = |point| cylinder.get_signed_distance(&point).unwrap();
+
+        assert!((distance(vec3(0.0, 0.0, 0.0)) + 1.0).abs() < 1e-9);
+        assert!((distance(vec3(0.0, 1.5, 0.0)) + 0.5).abs() < 1e-9);
+        assert!((distance(vec3(3.0, 0.0, 0.0)) - 2.0).abs() < 1e-9);
+        assert!((distance(vec3(0.0, 4.0, 0.0)) - 2.0).abs() < 1e-9);
+        assert!((distance(vec3(4.0, 6.0, 0.0)) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
     fn cylinder_bounding_box_contains_centers_but_not_more_along_axis() {
         let center_a = vec3(21.0, -33.0, 98.0);
         let center_b = vec3(-12.0, 23.0, -10.0);
@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use crate::linalg::BoundingBox;
+
+use nalgebra_glm::{inverse, vec4, vec4_to_vec3, DMat4, DVec3};
+
+use crate::shapes::shape;
+
+/// Ellipsoid given as the unit sphere transformed by the inverse of `inv_instance_matrix`
+pub struct Ellipsoid {
+    inv_instance_matrix: DMat4,
+    center: DVec3,
+    object_id: u32,
+}
+
+impl Ellipsoid {
+    pub fn new(inv_instance_matrix: DMat4, object_id: u32) -> Self {
+        let center = vec4_to_vec3(&(inverse(&inv_instance_matrix) * vec4(0.0, 0.0, 0.0, 1.0)));
+
+        Ellipsoid {
+            inv_instance_matrix,
+            center,
+            object_id,
+        }
+    }
+}
+
+impl shape::Shape for Ellipsoid {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        let transformed_point =
+            vec4_to_vec3(&(self.inv_instance_matrix * vec4(point.x, point.y, point.z, 1.0)));
+        transformed_point.magnitude_squared() < 1.0
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let instance_matrix = inverse(&self.inv_instance_matrix);
+
+        BoundingBox::get_transformed_base_cube(&instance_matrix)
+    }
+
+    fn get_volume(&self) -> f64 {
+        let inv_determinant = self
+            .inv_instance_matrix
+            .fixed_view::<3, 3>(0, 0)
+            .determinant();
+        4.0 / 3.0 * PI / inv_determinant.abs()
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.center).magnitude()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Ellipsoid;
+    use crate::shapes::Shape;
+
+    use nalgebra_glm::{inverse, scale, translate, vec3, DMat4};
+
+    #[test]
+    fn identity_ellipsoid_is_unit_sphere() {
+        let ellipsoid = Ellipsoid::new(DMat4::identity(), 0);
+
+        assert!(ellipsoid.contains_point(&vec3(0.0, 0.0, 0.0)));
+        assert!(ellipsoid.contains_point(&vec3(0.0, 0.99, 0.0)));
+        assert!(!ellipsoid.contains_point(&vec3(0.6, 0.6, 0.6)));
+    }
+
+    #[test]
+    fn scaled_and_translated_ellipsoid_contains_the_right_points() {
+        let matrix = scale(
+            &translate(&DMat4::identity(), &vec3(10.0, 0.0, 0.0)),
+            &vec3(3.0, 1.0, 0.5),
+        );
+        let ellipsoid = Ellipsoid::new(inverse(&matrix), 0);
+
+        assert!(ellipsoid.contains_point(&vec3(12.9, 0.0, 0.0)));
+        assert!(!ellipsoid.contains_point(&vec3(10.0, 0.0, 0.6)));
+        assert!(!ellipsoid.contains_point(&vec3(12.0, 0.8, 0.0)));
+
+        let bounding_box = ellipsoid.create_bounding_box();
+        assert!((bounding_box.min - vec3(7.0, -1.0, -0.5)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(13.0, 1.0, 0.5)).magnitude() < 1e-9);
+    }
+}
@@ -0,0 +1,223 @@
+/// Constraint `dot(normal, x) <= offset`
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub normal: Vec<f64>,
+    pub offset: f64,
+}
+
+/// Maximizes `dot(objective, x)` subject to the constraints and `lower <= x <= upper`, using
+/// Seidel's randomized incremental algorithm, which takes expected linear time in the number
+/// of constraints for a fixed dimension. Returns `None` if the constraints are infeasible.
+/// `epsilon` is the amount by which constraints may be violated
+pub fn maximize(
+    objective: &[f64],
+    constraints: &[Constraint],
+    lower: &[f64],
+    upper: &[f64],
+    epsilon: f64,
+) -> Option<Vec<f64>> {
+    let mut shuffled = constraints.to_vec();
+    shuffle(&mut shuffled);
+
+    solve(objective, &shuffled, lower, upper, epsilon)
+}
+
+/// Fisher-Yates shuffle with a fixed xorshift seed, so results are reproducible
+fn shuffle(constraints: &mut [Constraint]) {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    for i in (1..constraints.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        constraints.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn solve(
+    objective: &[f64],
+    constraints: &[Constraint],
+    lower: &[f64],
+    upper: &[f64],
+    epsilon: f64,
+) -> Option<Vec<f64>> {
+    if lower.iter().zip(upper).any(|(lower, upper)| lower > upper) {
+        return None;
+    }
+    if objective.len() == 1 {
+        return solve_interval(objective[0], constraints, lower[0], upper[0], epsilon);
+    }
+
+    // Start at the optimal corner of the box, and move onto each violated constraint. The new
+    // optimum then lies on the constraint's plane, which is a problem of one dimension less
+    let mut x: Vec<f64> = (0..objective.len())
+        .map(|k| {
+            if objective[k] >= 0.0 {
+                upper[k]
+            } else {
+                lower[k]
+            }
+        })
+        .collect();
+
+    for (i, constraint) in constraints.iter().enumerate() {
+        if dot(&constraint.normal, &x) <= constraint.offset + epsilon {
+            continue;
+        }
+        x = solve_on_plane(
+            constraint,
+            objective,
+            &constraints[..i],
+            lower,
+            upper,
+            epsilon,
+        )?;
+    }
+
+    Some(x)
+}
+
+fn solve_interval(
+    objective: f64,
+    constraints: &[Constraint],
+    mut lower: f64,
+    mut upper: f64,
+    epsilon: f64,
+) -> Option<Vec<f64>> {
+    for constraint in constraints {
+        let a = constraint.normal[0];
+        if a.abs() <= f64::EPSILON {
+            if constraint.offset < -epsilon {
+                return None;
+            }
+        } else if a > 0.0 {
+            upper = upper.min(constraint.offset / a);
+        } else {
+            lower = lower.max(constraint.offset / a);
+        }
+    }
+
+    if lower > upper + epsilon {
+        None
+    } else if objective >= 0.0 {
+        Some(upper.max(lower))
+    } else {
+        Some(lower)
+    }
+    .map(|x| vec![x])
+}
+
+/// Solves the problem restricted to `dot(plane.normal, x) = plane.offset`, by eliminating the
+/// variable with the largest coefficient in `plane.normal`
+fn solve_on_plane(
+    plane: &Constraint,
+    objective: &[f64],
+    constraints: &[Constraint],
+    lower: &[f64],
+    upper: &[f64],
+    epsilon: f64,
+) -> Option<Vec<f64>> {
+    let k = (0..plane.normal.len())
+        .max_by(|&a, &b| plane.normal[a].abs().total_cmp(&plane.normal[b].abs()))?;
+    let pivot = plane.normal[k];
+    if pivot.abs() <= f64::EPSILON {
+        return None;
+    }
+
+    // x_k = (plane.offset - sum of plane.normal[j] * x_j over j != k) / pivot
+    let without_k = |values: &[f64]| -> Vec<f64> {
+        values
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != k)
+            .map(|(_, &value)| value)
+            .collect()
+    };
+    let substitute = |normal: &[f64], offset: f64| Constraint {
+        normal: without_k(
+            &(0..normal.len())
+                .map(|j| normal[j] - normal[k] * plane.normal[j] / pivot)
+                .collect::<Vec<_>>(),
+        ),
+        offset: offset - normal[k] * plane.offset / pivot,
+    };
+
+    let mut reduced_constraints: Vec<Constraint> = constraints
+        .iter()
+        .map(|constraint| substitute(&constraint.normal, constraint.offset))
+        .collect();
+
+    // The bounds of x_k become general constraints
+    let mut unit = vec![0.0; plane.normal.len()];
+    unit[k] = 1.0;
+    reduced_constraints.push(substitute(&unit, upper[k]));
+    unit[k] = -1.0;
+    reduced_constraints.push(substitute(&unit, -lower[k]));
+
+    let reduced_objective = substitute(objective, 0.0).normal;
+    let reduced = solve(
+        &reduced_objective,
+        &reduced_constraints,
+        &without_k(lower),
+        &without_k(upper),
+        epsilon,
+    )?;
+
+    let mut x = reduced;
+    let x_k = (plane.offset
+        - (0..plane.normal.len())
+            .filter(|&j| j != k)
+            .zip(&x)
+            .map(|(j, value)| plane.normal[j] * value)
+            .sum::<f64>())
+        / pivot;
+    x.insert(k, x_k);
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{maximize, Constraint};
+
+    #[test]
+    fn optimum_of_triangle_is_its_top_corner() {
+        // x >= 0, y >= 0, x + y <= 2, maximizing y - x / 2
+        let constraints = [
+            (vec![-1.0, 0.0], 0.0),
+            (vec![0.0, -1.0], 0.0),
+            (vec![1.0, 1.0], 2.0),
+        ]
+        .map(|(normal, offset)| Constraint { normal, offset });
+
+        let x = maximize(
+            &[-0.5, 1.0],
+            &constraints,
+            &[-10.0, -10.0],
+            &[10.0, 10.0],
+            1e-12,
+        )
+        .unwrap();
+
+        assert!((x[0] - 0.0).abs() < 1e-9);
+        assert!((x[1] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn infeasible_constraints_have_no_solution() {
+        let constraints = [(vec![1.0, 0.0, 0.0], -1.0), (vec![-1.0, 0.0, 0.0], -1.0)]
+            .map(|(normal, offset)| Constraint { normal, offset });
+
+        assert!(maximize(
+            &[0.0, 0.0, 1.0],
+            &constraints,
+            &[-10.0; 3],
+            &[10.0; 3],
+            1e-12
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,31 @@
+mod capsule;
+mod composite;
+mod cone;
+mod convex_hull;
+mod convex_polyhedron;
+mod cylinder;
+mod ellipsoid;
+mod linear_program;
+mod oriented_box;
+mod pipe_run;
+mod prism;
+mod shape;
+mod sphere;
+mod tolerance;
+mod torus_segment;
+mod triangle_mesh;
+
+pub use capsule::Capsule;
+pub use composite::{Composite, CompositeOperation};
+pub use cone::Cone;
+pub use convex_polyhedron::{ConvexPolyhedron, HalfSpace};
+pub use cylinder::Cylinder;
+pub use ellipsoid::Ellipsoid;
+pub use oriented_box::OrientedBox;
+pub use pipe_run::PipeRun;
+pub use prism::Prism;
+pub use shape::Shape;
+pub use sphere::Sphere;
+pub use tolerance::Tolerance;
+pub use torus_segment::TorusSegment;
+pub use triangle_mesh::TriangleMesh;
@@ -1,19 +1,29 @@
 use crate::linalg::BoundingBox;
 
-use nalgebra_glm::{inverse, vec4, vec4_to_vec3, DMat4, DVec3};
+use nalgebra_glm::{abs, inverse, vec4, vec4_to_vec3, DMat4, DVec3};
 
 use crate::shapes::shape;
 
 pub struct OrientedBox {
     inv_instance_matrix: DMat4,
-    object_id: u16,
+    center: DVec3,
+    /// Half-size along each of the box axes, in world units
+    half_extents: DVec3,
+    object_id: u32,
 }
 
 impl OrientedBox {
-    pub fn new(inv_instance_matrix: DMat4, object_id: u16) -> Self {
+    pub fn new(inv_instance_matrix: DMat4, object_id: u32) -> Self {
+        let instance_matrix = inverse(&inv_instance_matrix);
+        let center = vec4_to_vec3(&(instance_matrix * vec4(0.0, 0.0, 0.0, 1.0)));
+        let half_extents =
+            DVec3::from_fn(|axis, _| instance_matrix.fixed_view::<3, 1>(0, axis).magnitude());
+
         OrientedBox {
-            inv_instance_matrix: inv_instance_matrix,
-            object_id: object_id,
+            inv_instance_matrix,
+            center,
+            half_extents,
+            object_id,
         }
     }
 }
@@ -31,14 +41,38 @@ impl shape::Shape for OrientedBox {
         BoundingBox::get_transformed_base_cube(&instance_matrix)
     }
 
-    fn get_object_id(&self) -> u16 {
+    fn get_volume(&self) -> f64 {
+        let inv_determinant = self
+            .inv_instance_matrix
+            .fixed_view::<3, 3>(0, 0)
+            .determinant();
+        8.0 / inv_determinant.abs()
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.center).magnitude()
+    }
+
+    /// Exact for boxes without shear, where the box axes are orthogonal
+    fn get_signed_distance(&self, point: &DVec3) -> Option<f64> {
+        let transformed_point =
+            vec4_to_vec3(&(self.inv_instance_matrix * vec4(point.x, point.y, point.z, 1.0)));
+        let axis_distances =
+            (abs(&transformed_point) - DVec3::repeat(1.0)).component_mul(&self.half_extents);
+
+        let outside_distance = axis_distances.sup(&DVec3::zeros()).magnitude();
+        let inside_distance = axis_distances.max().min(0.0);
+
+        Some(outside_distance + inside_distance)
+    }
+
+    fn get_object_id(&self) -> u32 {
         self.object_id
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use wasm_bindgen_test::wasm_bindgen_test;
 
     use super::OrientedBox;
     use crate::shapes::Shape;
@@ -48,13 +82,13 @@ mod tests {
         DMat4,
     };
 
-    #[wasm_bindgen_test]
+    #[test]
     fn identity_oriented_box_contains_origin() {
         let ob = OrientedBox::new(DMat4::identity(), 0);
         assert!(ob.contains_point(&vec3(0.0, 0.0, 0.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn identity_oriented_box_does_not_contain_doubled_cardinal_unit_vectors() {
         let ob = OrientedBox::new(DMat4::identity(), 0);
         assert!(!ob.contains_point(&vec3(2.0, 0.0, 0.0)));
@@ -62,7 +96,7 @@ mod tests {
         assert!(!ob.contains_point(&vec3(0.0, 0.0, 2.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn translated_oriented_box_contains_only_translated_origin() {
         let ob = OrientedBox::new(
             nalgebra_glm::inverse(&translate(&DMat4::identity(), &vec3(2.0, 2.0, 2.0))),
@@ -72,7 +106,7 @@ mod tests {
         assert!(ob.contains_point(&vec3(2.0, 2.0, 2.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn scaled_oriented_box_contains_only_points_in_scaled_direction() {
         let ob = OrientedBox::new(inverse(&scale(&DMat4::identity(), &vec3(3.0, 1.0, 1.0))), 0);
         assert!(ob.contains_point(&vec3(2.0, 0.0, 0.0)));
@@ -80,7 +114,7 @@ mod tests {
         assert!(!ob.contains_point(&vec3(0.0, 0.0, 2.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn scaled_and_rotated_oriented_box_contains_the_right_points() {
         let matrix = inverse(&scale(
             &rotate_x(&DMat4::identity(), half_pi()),
@@ -93,7 +127,7 @@ mod tests {
         assert!(ob.contains_point(&vec3(0.0, 0.0, 2.0)));
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn identitys_bounding_box_is_identity() {
         let original_box = OrientedBox::new(DMat4::identity(), 0);
         let bounding_box = original_box.create_bounding_box();
@@ -102,7 +136,7 @@ mod tests {
         assert!(comp_max(&abs(&(bounding_box.max - vec3(1.0, 1.0, 1.0)))) < 1e-2);
     }
 
-    #[wasm_bindgen_test]
+    #[test]
     fn bounding_box_contains_transformed_origin() {
         let matrix = translate(
             &scale(&rotate_x(&DMat4::identity(), 1.0), &vec3(2.0, 1.0, 0.5)),
@@ -115,4 +149,33 @@ mod tests {
         let transformed_origin = vec4_to_vec3(&(matrix * vec4(0.0, 0.0, 0.0, 1.0)));
         bounding_box.contains_point(&transformed_origin);
     }
+
+    #[test]
+    fn scaled_box_volume_and_center_distance() {
+        let matrix = translate(
+            &scale(&DMat4::identity(), &vec3(2.0, 1.0, 0.5)),
+            &vec3(1.0, 0.0, 0.0),
+        );
+        let original_box = OrientedBox::new(inverse(&matrix), 0);
+
+        assert!((original_box.get_volume() - 8.0).abs() < 1e-9);
+        assert!(original_box.get_distance_to_center(&vec3(2.0, 0.0, 0.0)) < 1e-9);
+    }
+
+    #[test]
+    fn rotated_box_signed_distance_is_in_world_units() {
+        let matrix = scale(
+            &rotate_x(&DMat4::identity(), half_pi()),
+            &vec3(2.0, 1.0, 0.5),
+        );
+        let original_box = OrientedBox::new(inverse(&matrix), 0);
+
+        let distance = |point| original_box.get_signed_distance(&point).unwrap();
+
+        assert!((distance(vec3(0.0, 0.0, 0.0)) + 0.5).abs() < 1e-9);
+        assert!((distance(vec3(3.0, 0.0, 0.0)) - 1.0).abs() < 1e-9);
+        assert!((distance(vec3(0.0, 1.0, 0.0)) - 0.5).abs() < 1e-9);
+        assert!((distance(vec3(0.0, 0.0, 1.5)) - 0.5).abs() < 1e-9);
+        assert!((distance(vec3(3.0, 1.0, 0.0)) - 0.5_f64.hypot(1.0)).abs() < 1e-9);
+    }
 }
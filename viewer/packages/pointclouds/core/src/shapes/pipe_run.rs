@@ -0,0 +1,118 @@
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+use crate::shapes::capsule::Capsule;
+use crate::shapes::shape::Shape;
+
+/// A pipe following a polyline of centers with constant radius. Each segment is a capsule,
+/// so bends between segments are rounded
+pub struct PipeRun {
+    segments: Vec<Capsule>,
+    object_id: u32,
+}
+
+impl PipeRun {
+    pub fn new(centers: &[DVec3], radius: f64, object_id: u32) -> Self {
+        let segments = centers
+            .windows(2)
+            .map(|pair| Capsule::new(pair[0], pair[1], radius, object_id))
+            .collect();
+
+        PipeRun {
+            segments,
+            object_id,
+        }
+    }
+}
+
+impl Shape for PipeRun {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| segment.contains_point(point))
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let mut bounding_box: BoundingBox = Default::default();
+        self.segments
+            .iter()
+            .for_each(|segment| bounding_box.add_box(&segment.create_bounding_box()));
+
+        bounding_box
+    }
+
+    fn create_bounding_boxes(&self) -> Vec<BoundingBox> {
+        self.segments
+            .iter()
+            .map(|segment| segment.create_bounding_box())
+            .collect()
+    }
+
+    /// Sum of the segment volumes, so the overlap at each joint is counted twice
+    fn get_volume(&self) -> f64 {
+        self.segments
+            .iter()
+            .map(|segment| segment.get_volume())
+            .sum()
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        self.segments
+            .iter()
+            .map(|segment| segment.get_distance_to_center(point))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::PipeRun;
+    use crate::shapes::Shape;
+
+    #[test]
+    fn l_shaped_pipe_run_contains_points_along_both_legs_only() {
+        let pipe_run = PipeRun::new(
+            &[
+                vec3(0.0, 0.0, 0.0),
+                vec3(10.0, 0.0, 0.0),
+                vec3(10.0, 10.0, 0.0),
+            ],
+            0.5,
+            0,
+        );
+
+        assert!(pipe_run.contains_point(&vec3(5.0, 0.2, 0.0)));
+        assert!(pipe_run.contains_point(&vec3(10.2, 5.0, 0.0)));
+        assert!(pipe_run.contains_point(&vec3(10.3, -0.3, 0.0)));
+        assert!(!pipe_run.contains_point(&vec3(5.0, 5.0, 0.0)));
+        assert!(!pipe_run.contains_point(&vec3(5.0, 0.0, 0.6)));
+    }
+
+    #[test]
+    fn pipe_run_has_one_bounding_box_per_segment() {
+        let pipe_run = PipeRun::new(
+            &[
+                vec3(0.0, 0.0, 0.0),
+                vec3(10.0, 0.0, 0.0),
+                vec3(10.0, 10.0, 0.0),
+                vec3(10.0, 10.0, 10.0),
+            ],
+            0.5,
+            0,
+        );
+
+        let boxes = pipe_run.create_bounding_boxes();
+        assert_eq!(boxes.len(), 3);
+        assert!(!boxes.iter().any(|b| b.contains_point(&vec3(5.0, 5.0, 0.0))));
+
+        let full_box = pipe_run.create_bounding_box();
+        assert!((full_box.min - vec3(-0.5, -0.5, -0.5)).magnitude() < 1e-9);
+        assert!((full_box.max - vec3(10.5, 10.5, 10.5)).magnitude() < 1e-9);
+    }
+}
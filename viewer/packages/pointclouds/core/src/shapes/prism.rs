@@ -0,0 +1,309 @@
+use nalgebra_glm::{dot, vec2, DVec2, DVec3};
+
+use crate::linalg::{create_orthonormal_basis, BoundingBox};
+use crate::shapes::shape::Shape;
+use crate::shapes::tolerance::Tolerance;
+
+/// A possibly concave polygon extruded along an axis. The polygon vertices are projected onto
+/// the plane orthogonal to `axis`, and the extrusion covers the points whose coordinate
+/// along `axis` (i.e. `dot(point, axis)`) lies between `min_height` and `max_height`
+pub struct Prism {
+    polygon: Vec<DVec2>,
+    /// How far the footprint is grown out from each edge, from vertex `i` to vertex `i + 1`
+    edge_margins: Vec<f64>,
+    axis: DVec3,
+    basis_u: DVec3,
+    basis_v: DVec3,
+    min_height: f64,
+    max_height: f64,
+    object_id: u32,
+}
+
+impl Prism {
+    pub fn new(
+        vertices: &[DVec3],
+        axis: DVec3,
+        min_height: f64,
+        max_height: f64,
+        object_id: u32,
+    ) -> Self {
+        let axis = axis.normalize();
+        let (basis_u, basis_v) = create_orthonormal_basis(&axis);
+
+        let polygon: Vec<DVec2> = vertices
+            .iter()
+            .map(|vertex| vec2(dot(vertex, &basis_u), dot(vertex, &basis_v)))
+            .collect();
+
+        Prism {
+            edge_margins: vec![0.0; polygon.len()],
+            polygon,
+            axis,
+            basis_u,
+            basis_v,
+            min_height,
+            max_height,
+            object_id,
+        }
+    }
+
+    /// Grows the footprint out from each edge by the margin for the footprint's half-width
+    /// across the edge, and the height range about its middle. Scaling the footprint about its
+    /// center instead would move edges inward where the center lies outside a concave polygon
+    pub fn inflated(self, tolerance: &Tolerance) -> Self {
+        let edge_margins = self
+            .edges()
+            .map(|(a, b, _)| {
+                let edge = b - a;
+                if edge.magnitude() == 0.0 {
+                    return 0.0;
+                }
+
+                let normal = vec2(-edge.y, edge.x).normalize();
+                let offsets = self.polygon.iter().map(|vertex| dot(&normal, vertex));
+                let width = offsets.clone().fold(f64::NEG_INFINITY, f64::max)
+                    - offsets.fold(f64::INFINITY, f64::min);
+                tolerance.get_margin(width / 2.0)
+            })
+            .collect();
+
+        let middle_height = (self.min_height + self.max_height) / 2.0;
+        let half_height = tolerance.inflate((self.max_height - self.min_height) / 2.0);
+
+        Prism {
+            edge_margins,
+            min_height: middle_height - half_height,
+            max_height: middle_height + half_height,
+            ..self
+        }
+    }
+
+    fn get_center(&self) -> DVec3 {
+        let footprint_center = self.polygon.iter().sum::<DVec2>() / self.polygon.len() as f64;
+        self.to_world(&footprint_center, (self.min_height + self.max_height) / 2.0)
+    }
+
+    fn to_world(&self, point: &DVec2, height: f64) -> DVec3 {
+        self.basis_u * point.x + self.basis_v * point.y + self.axis * height
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&DVec2, &DVec2, f64)> {
+        self.polygon.iter().enumerate().map(|(i, a)| {
+            let b = &self.polygon[(i + 1) % self.polygon.len()];
+            (a, b, self.edge_margins[i])
+        })
+    }
+
+    /// Points of the exact footprint closer than a negative margin to its edge are left out,
+    /// and points outside it closer than a positive margin are taken in
+    fn footprint_contains(&self, point: &DVec2) -> bool {
+        if winding_number(&self.polygon, point) != 0 {
+            self.edges()
+                .all(|(a, b, margin)| margin >= 0.0 || distance_to_segment(a, b, point) >= -margin)
+        } else {
+            self.edges()
+                .any(|(a, b, margin)| distance_to_segment(a, b, point) < margin)
+        }
+    }
+}
+
+fn distance_to_segment(a: &DVec2, b: &DVec2, point: &DVec2) -> f64 {
+    let edge = b - a;
+    let length_squared = edge.magnitude_squared();
+    let t = if length_squared > 0.0 {
+        (dot(&(point - a), &edge) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (point - (a + edge * t)).magnitude()
+}
+
+/// Which side of the directed line from `a` to `b` the point is on.
+/// Positive for left, negative for right, zero if on the line
+fn is_left(a: &DVec2, b: &DVec2, point: &DVec2) -> f64 {
+    (b.x - a.x) * (point.y - a.y) - (point.x - a.x) * (b.y - a.y)
+}
+
+/// Computes the winding number of the polygon around the point. Only edge crossings are
+/// counted, each with a half-open rule on the edge's y-range, so rays through
+/// vertices are not counted twice
+fn winding_number(polygon: &[DVec2], point: &DVec2) -> i32 {
+    let mut winding = 0;
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = &polygon[(i + 1) % polygon.len()];
+
+        if a.y <= point.y {
+            if b.y > point.y && is_left(a, b, point) > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= point.y && is_left(a, b, point) < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    winding
+}
+
+impl Shape for Prism {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        let height = dot(point, &self.axis);
+        if height <= self.min_height || height >= self.max_height {
+            return false;
+        }
+
+        let projected = vec2(dot(point, &self.basis_u), dot(point, &self.basis_v));
+        self.footprint_contains(&projected)
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let mut bounding_box: BoundingBox = self
+            .polygon
+            .iter()
+            .flat_map(|vertex| {
+                [
+                    self.to_world(vertex, self.min_height),
+                    self.to_world(vertex, self.max_height),
+                ]
+            })
+            .collect();
+
+        let max_margin = self.edge_margins.iter().copied().fold(0.0, f64::max);
+        bounding_box.min.add_scalar_mut(-max_margin);
+        bounding_box.max.add_scalar_mut(max_margin);
+        bounding_box
+    }
+
+    /// The area of the footprint is grown by each edge's length times its margin, which leaves
+    /// out the rounded corners
+    fn get_volume(&self) -> f64 {
+        let twice_area: f64 = self.edges().map(|(a, b, _)| a.x * b.y - b.x * a.y).sum();
+        let margin_area: f64 = self
+            .edges()
+            .map(|(a, b, margin)| (b - a).magnitude() * margin)
+            .sum();
+
+        (twice_area.abs() / 2.0 + margin_area).max(0.0) * (self.max_height - self.min_height)
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.get_center()).magnitude()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{comp_min, vec2, vec3};
+
+    use super::{winding_number, Prism};
+    use crate::shapes::{Shape, Tolerance};
+
+    fn create_l_shaped_room() -> Prism {
+        let footprint = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(4.0, 0.0, 0.0),
+            vec3(4.0, 1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+            vec3(1.0, 4.0, 0.0),
+            vec3(0.0, 4.0, 0.0),
+        ];
+
+        Prism::new(&footprint, vec3(0.0, 0.0, 1.0), 2.0, 5.0, 0)
+    }
+
+    #[test]
+    fn concave_prism_excludes_points_in_notch() {
+        let room = create_l_shaped_room();
+
+        assert!(room.contains_point(&vec3(0.5, 0.5, 3.0)));
+        assert!(room.contains_point(&vec3(3.5, 0.5, 3.0)));
+        assert!(room.contains_point(&vec3(0.5, 3.5, 3.0)));
+        assert!(!room.contains_point(&vec3(2.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn prism_respects_height_range() {
+        let room = create_l_shaped_room();
+
+        assert!(!room.contains_point(&vec3(0.5, 0.5, 1.9)));
+        assert!(!room.contains_point(&vec3(0.5, 0.5, 5.1)));
+    }
+
+    #[test]
+    fn prism_along_tilted_axis_has_bounding_box_containing_all_corners() {
+        let axis = vec3(1.0, 1.0, 0.0);
+        let footprint = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 2.0),
+            vec3(1.0, -1.0, 1.0),
+        ];
+        let prism = Prism::new(&footprint, axis, -1.0, 1.0, 0);
+        let bounding_box = prism.create_bounding_box();
+
+        let unit_axis = axis.normalize();
+        for vertex in footprint {
+            for height in [-1.0, 1.0] {
+                let corner = vertex + unit_axis * height;
+                assert!(comp_min(&(corner - bounding_box.min)) > -1e-9);
+                assert!(comp_min(&(bounding_box.max - corner)) > -1e-9);
+            }
+        }
+
+        assert!(prism.contains_point(&(vec3(0.3, -0.3, 1.0) + unit_axis * 0.5)));
+    }
+
+    #[test]
+    fn ray_through_vertex_is_counted_once() {
+        let diamond = [
+            vec2(0.0, -1.0),
+            vec2(1.0, 0.0),
+            vec2(0.0, 1.0),
+            vec2(-1.0, 0.0),
+        ];
+
+        assert_ne!(winding_number(&diamond, &vec2(0.0, 0.0)), 0);
+        assert_eq!(winding_number(&diamond, &vec2(-2.0, 0.0)), 0);
+        assert_eq!(winding_number(&diamond, &vec2(-2.0, 1.0)), 0);
+    }
+
+    #[test]
+    fn inflated_concave_prism_keeps_points_of_exact_footprint() {
+        let room = create_l_shaped_room().inflated(&Tolerance::default());
+
+        assert!(room.contains_point(&vec3(1.5, 0.99, 3.0)));
+        assert!(room.contains_point(&vec3(0.99, 1.5, 3.0)));
+        assert!(room.contains_point(&vec3(2.0, 1.05, 3.0)));
+        assert!(!room.contains_point(&vec3(2.0, 1.07, 3.0)));
+        assert!(!room.contains_point(&vec3(2.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn inflated_prism_grows_out_from_its_edges() {
+        let footprint = [
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(1.0, 1.0, 0.0),
+            vec3(-1.0, 1.0, 0.0),
+        ];
+        let tolerance = Tolerance {
+            relative_factor: Some(1.5),
+            absolute_margin: None,
+        };
+        let prism = Prism::new(&footprint, vec3(0.0, 0.0, 1.0), -1.0, 1.0, 0).inflated(&tolerance);
+
+        assert!(prism.contains_point(&vec3(1.45, 0.0, 1.45)));
+        assert!(!prism.contains_point(&vec3(1.55, 0.0, 0.0)));
+        assert!(prism.contains_point(&vec3(1.3, 1.3, 0.0)));
+        assert!(!prism.contains_point(&vec3(1.4, 1.4, 0.0)));
+    }
+
+    #[test]
+    fn concave_prism_volume_is_footprint_area_times_height() {
+        assert!((create_l_shaped_room().get_volume() - 7.0 * 3.0).abs() < 1e-9);
+    }
+}
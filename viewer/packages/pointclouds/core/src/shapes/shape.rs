@@ -0,0 +1,30 @@
+use nalgebra_glm::DVec3;
+
+use crate::linalg::BoundingBox;
+
+pub trait Shape {
+    fn create_bounding_box(&self) -> BoundingBox;
+
+    /// Bounding boxes used for culling octree nodes. Shapes made up of several spatially
+    /// separated parts can return one box per part
+    fn create_bounding_boxes(&self) -> Vec<BoundingBox> {
+        vec![self.create_bounding_box()]
+    }
+
+    fn contains_point(&self, point: &DVec3) -> bool;
+
+    /// Volume of the shape. May be an approximation for shapes where the exact value is costly
+    fn get_volume(&self) -> f64;
+
+    /// Distance from the point to the shape's center, or to its center axis for elongated
+    /// shapes such as cylinders and pipes
+    fn get_distance_to_center(&self, point: &DVec3) -> f64;
+
+    /// Distance from the point to the surface of the shape, negative for points inside.
+    /// `None` for shapes that do not support it
+    fn get_signed_distance(&self, _point: &DVec3) -> Option<f64> {
+        None
+    }
+
+    fn get_object_id(&self) -> u32;
+}
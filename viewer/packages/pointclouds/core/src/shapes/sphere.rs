@@ -0,0 +1,79 @@
+use std::f64::consts::PI;
+
+use nalgebra_glm::{vec3, DVec3};
+
+use crate::linalg::BoundingBox;
+use crate::shapes::shape::Shape;
+
+pub struct Sphere {
+    center: DVec3,
+    radius: f64,
+    object_id: u32,
+}
+
+impl Sphere {
+    pub fn new(center: DVec3, radius: f64, object_id: u32) -> Self {
+        Sphere {
+            center,
+            radius,
+            object_id,
+        }
+    }
+}
+
+impl Shape for Sphere {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        (point - self.center).magnitude_squared() < self.radius * self.radius
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let radius_vec = vec3(self.radius, self.radius, self.radius);
+
+        BoundingBox {
+            min: self.center - radius_vec,
+            max: self.center + radius_vec,
+        }
+    }
+
+    fn get_volume(&self) -> f64 {
+        4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.center).magnitude()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::vec3;
+
+    use super::Sphere;
+    use crate::shapes::Shape;
+
+    #[test]
+    fn sphere_contains_center_but_not_points_outside_radius() {
+        let center = vec3(10.0, -3.0, 2.5);
+        let sphere = Sphere::new(center, 2.0, 0);
+
+        assert!(sphere.contains_point(&center));
+        assert!(sphere.contains_point(&(center + vec3(1.1, 1.1, 1.1))));
+        assert!(!sphere.contains_point(&(center + vec3(1.2, 1.2, 1.2))));
+        assert!(!sphere.contains_point(&(center + vec3(0.0, 0.0, 2.1))));
+    }
+
+    #[test]
+    fn sphere_bounding_box_touches_sphere_along_axes() {
+        let center = vec3(1.0, 2.0, 3.0);
+        let sphere = Sphere::new(center, 0.5, 0);
+        let bounding_box = sphere.create_bounding_box();
+
+        assert!(bounding_box.contains_point(&(center + vec3(0.0, 0.0, 0.5))));
+        assert!(bounding_box.contains_point(&(center - vec3(0.5, 0.0, 0.0))));
+        assert!(!bounding_box.contains_point(&(center + vec3(0.0, 0.51, 0.0))));
+    }
+}
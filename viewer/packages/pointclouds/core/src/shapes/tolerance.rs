@@ -0,0 +1,81 @@
+/// How much shapes are grown before points are assigned to them, to make up for noise in the
+/// point cloud and inaccuracies in the modelled geometry. Each extent of a shape (a radius, the
+/// half-size along a box axis, the half-width of a polyhedron, prism or mesh along a face's
+/// normal) is scaled by `relative_factor`, but grown by at most `absolute_margin` metres. Leaving out one of the two
+/// applies only the other, leaving out both keeps the shape as is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    pub relative_factor: Option<f64>,
+    pub absolute_margin: Option<f64>,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            relative_factor: Some(1.15),
+            absolute_margin: Some(0.06),
+        }
+    }
+}
+
+impl Tolerance {
+    pub fn inflate(&self, extent: f64) -> f64 {
+        match (self.relative_factor, self.absolute_margin) {
+            (Some(factor), Some(margin)) => (extent * factor).min(extent + margin),
+            (Some(factor), None) => extent * factor,
+            (None, Some(margin)) => extent + margin,
+            (None, None) => extent,
+        }
+    }
+
+    /// How far a face moves out when the extent behind it, i.e. the half-width of the shape
+    /// along the face's normal, is inflated
+    pub fn get_margin(&self, extent: f64) -> f64 {
+        self.inflate(extent) - extent
+    }
+
+    /// The factor to scale a shape by about its center so that the given extent is inflated.
+    /// Degenerate extents are left unscaled
+    pub fn get_scale_factor(&self, extent: f64) -> f64 {
+        if extent > 0.0 {
+            self.inflate(extent) / extent
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tolerance;
+
+    #[test]
+    fn default_tolerance_caps_growth_of_large_extents() {
+        let tolerance = Tolerance::default();
+
+        assert!((tolerance.inflate(0.1) - 0.115).abs() < 1e-12);
+        assert!((tolerance.inflate(10.0) - 10.06).abs() < 1e-12);
+        assert!((tolerance.get_margin(0.025) - 0.00375).abs() < 1e-12);
+    }
+
+    #[test]
+    fn missing_parts_of_tolerance_are_not_applied() {
+        let relative = Tolerance {
+            relative_factor: Some(2.0),
+            absolute_margin: None,
+        };
+        let absolute = Tolerance {
+            relative_factor: None,
+            absolute_margin: Some(0.5),
+        };
+        let exact = Tolerance {
+            relative_factor: None,
+            absolute_margin: None,
+        };
+
+        assert_eq!(relative.inflate(10.0), 20.0);
+        assert_eq!(absolute.inflate(10.0), 10.5);
+        assert_eq!(exact.inflate(10.0), 10.0);
+        assert_eq!(absolute.get_scale_factor(0.0), 1.0);
+    }
+}
@@ -0,0 +1,268 @@
+use std::f64::consts::{PI, TAU};
+
+use nalgebra_glm::{dot, vec3, DVec3};
+
+use crate::linalg::BoundingBox;
+use crate::shapes::shape::Shape;
+
+/// Section of a torus, e.g. a pipe elbow. The tube of radius `minor_radius` follows a circular arc
+/// of radius `major_radius` around `axis`. The arc starts at `start_angle` and sweeps counter-clockwise
+/// (right-handed around `axis`) to `end_angle`. Angles are in radians, measured from
+/// `reference_direction` projected into the plane orthogonal to `axis`
+pub struct TorusSegment {
+    center: DVec3,
+    axis: DVec3,
+    reference_direction: DVec3,
+    major_radius: f64,
+    minor_radius: f64,
+    start_angle: f64,
+    sweep_angle: f64,
+    object_id: u32,
+}
+
+impl TorusSegment {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        center: DVec3,
+        axis: DVec3,
+        reference_direction: DVec3,
+        major_radius: f64,
+        minor_radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        object_id: u32,
+    ) -> Self {
+        let axis = axis.normalize();
+        let reference_direction =
+            (reference_direction - axis * dot(&reference_direction, &axis)).normalize();
+
+        let sweep_angle = if end_angle - start_angle >= TAU {
+            TAU
+        } else {
+            (end_angle - start_angle).rem_euclid(TAU)
+        };
+
+        TorusSegment {
+            center,
+            axis,
+            reference_direction,
+            major_radius,
+            minor_radius,
+            start_angle,
+            sweep_angle,
+            object_id,
+        }
+    }
+
+    fn get_in_plane_basis(&self) -> (DVec3, DVec3) {
+        (
+            self.reference_direction,
+            self.axis.cross(&self.reference_direction),
+        )
+    }
+
+    fn angle_is_in_sweep(&self, angle: f64) -> bool {
+        (angle - self.start_angle).rem_euclid(TAU) <= self.sweep_angle
+    }
+
+    fn get_arc_point(&self, angle: f64) -> DVec3 {
+        let (u, v) = self.get_in_plane_basis();
+        self.center + (u * angle.cos() + v * angle.sin()) * self.major_radius
+    }
+
+    fn get_arc_tangent(&self, angle: f64) -> DVec3 {
+        let (u, v) = self.get_in_plane_basis();
+        v * angle.cos() - u * angle.sin()
+    }
+}
+
+impl Shape for TorusSegment {
+    fn contains_point(&self, point: &DVec3) -> bool {
+        let (u, v) = self.get_in_plane_basis();
+        let relative_point = point - self.center;
+
+        let height = dot(&relative_point, &self.axis);
+        let in_plane_vec = relative_point - self.axis * height;
+        let dist_from_center = in_plane_vec.magnitude();
+
+        let dist_from_arc_sq = (dist_from_center - self.major_radius).powi(2) + height * height;
+        if dist_from_arc_sq >= self.minor_radius * self.minor_radius {
+            return false;
+        }
+
+        let angle = dot(&in_plane_vec, &v).atan2(dot(&in_plane_vec, &u));
+        self.angle_is_in_sweep(angle)
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let end_angle = self.start_angle + self.sweep_angle;
+
+        // The tube's extent along a direction decreases monotonically with the angular distance
+        // from where the arc is furthest along that direction, so each face of the box is
+        // either at that extremal angle (if swept) or at one of the end caps
+        let mut bounding_box = BoundingBox::get_disc_bounding_box(
+            &self.get_arc_point(self.start_angle),
+            &self.get_arc_tangent(self.start_angle),
+            self.minor_radius,
+        );
+        bounding_box.add_box(&BoundingBox::get_disc_bounding_box(
+            &self.get_arc_point(end_angle),
+            &self.get_arc_tangent(end_angle),
+            self.minor_radius,
+        ));
+
+        let (u, v) = self.get_in_plane_basis();
+
+        for dim in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut direction = vec3(0.0, 0.0, 0.0);
+                direction[dim] = sign;
+
+                let in_plane_length =
+                    (dot(&direction, &u).powi(2) + dot(&direction, &v).powi(2)).sqrt();
+                let extremal_angle = dot(&direction, &v).atan2(dot(&direction, &u));
+
+                if in_plane_length < 1e-12 || self.angle_is_in_sweep(extremal_angle) {
+                    let extent = sign * self.center[dim]
+                        + self.major_radius * in_plane_length
+                        + self.minor_radius;
+                    if sign > 0.0 {
+                        bounding_box.max[dim] = bounding_box.max[dim].max(extent);
+                    } else {
+                        bounding_box.min[dim] = bounding_box.min[dim].min(-extent);
+                    }
+                }
+            }
+        }
+
+        bounding_box
+    }
+
+    fn get_volume(&self) -> f64 {
+        PI * self.minor_radius * self.minor_radius * self.major_radius * self.sweep_angle
+    }
+
+    /// Distance to the circle through the center of the tube
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        let relative_point = point - self.center;
+        let height = dot(&relative_point, &self.axis);
+        let dist_from_center = (relative_point - self.axis * height).magnitude();
+
+        ((dist_from_center - self.major_radius).powi(2) + height * height).sqrt()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    use nalgebra_glm::vec3;
+
+    use super::TorusSegment;
+    use crate::shapes::Shape;
+
+    fn create_quarter_elbow() -> TorusSegment {
+        TorusSegment::new(
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
+            2.0,
+            0.5,
+            0.0,
+            FRAC_PI_2,
+            0,
+        )
+    }
+
+    #[test]
+    fn quarter_elbow_contains_only_points_in_swept_quadrant() {
+        let elbow = create_quarter_elbow();
+
+        let diagonal = vec3(1.0, 1.0, 0.0).normalize() * 2.0;
+        assert!(elbow.contains_point(&diagonal));
+        assert!(elbow.contains_point(&(diagonal + vec3(0.0, 0.0, 0.4))));
+        assert!(!elbow.contains_point(&(diagonal + vec3(0.0, 0.0, 0.6))));
+        assert!(!elbow.contains_point(&-diagonal));
+        assert!(!elbow.contains_point(&vec3(2.0, -0.1, 0.0)));
+        assert!(!elbow.contains_point(&vec3(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn sweep_wraps_around_when_end_angle_is_smaller_than_start_angle() {
+        let elbow = TorusSegment::new(
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
+            2.0,
+            0.5,
+            1.5 * PI,
+            0.5 * PI,
+            0,
+        );
+
+        assert!(elbow.contains_point(&vec3(2.0, 0.0, 0.0)));
+        assert!(!elbow.contains_point(&vec3(-2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn quarter_elbow_bounding_box_is_tight() {
+        let bounding_box = create_quarter_elbow().create_bounding_box();
+
+        assert!((bounding_box.min - vec3(0.0, 0.0, -0.5)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(2.5, 2.5, 0.5)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn bounding_box_of_elbow_away_from_origin_is_tight() {
+        let elbow = TorusSegment::new(
+            vec3(100.0, 200.0, 50.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 0.0),
+            2.0,
+            0.5,
+            0.0,
+            FRAC_PI_2,
+            0,
+        );
+        let bounding_box = elbow.create_bounding_box();
+
+        assert!((bounding_box.min - vec3(100.0, 200.0, 49.5)).magnitude() < 1e-9);
+        assert!((bounding_box.max - vec3(102.5, 202.5, 50.5)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn tilted_elbow_bounding_box_contains_tube_surface() {
+        let axis = vec3(0.3, -1.0, 0.4).normalize();
+        let elbow = TorusSegment::new(
+            vec3(5.0, 1.0, -2.0),
+            axis,
+            vec3(0.0, 0.0, 1.0),
+            1.5,
+            0.25,
+            0.3,
+            2.4,
+            0,
+        );
+        let bounding_box = elbow.create_bounding_box();
+
+        for i in 0..=20 {
+            let angle = 0.3 + 2.1 * i as f64 / 20.0;
+            let arc_point = elbow.get_arc_point(angle);
+            let tangent = elbow.get_arc_tangent(angle);
+            let radial = (arc_point - vec3(5.0, 1.0, -2.0)).normalize();
+            for j in 0..16 {
+                let tube_angle = j as f64 * PI / 8.0;
+                let offset =
+                    (radial * tube_angle.cos() + radial.cross(&tangent) * tube_angle.sin()) * 0.249;
+                if i > 0 && i < 20 {
+                    assert!(elbow.contains_point(&(arc_point + offset * 0.99)));
+                }
+                assert!(bounding_box.contains_point(&(arc_point + offset)));
+            }
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use nalgebra_glm::{dot, vec3, DVec3};
+
+use crate::bvh::Bvh;
+use crate::linalg::BoundingBox;
+use crate::shapes::convex_hull::{get_epsilon, ConvexHull};
+use crate::shapes::shape::Shape;
+use crate::shapes::tolerance::Tolerance;
+
+/// Skewed direction used for inside/outside rays, chosen to make it unlikely that
+/// rays pass exactly through mesh edges or vertices in axis-aligned CAD geometry
+const RAY_DIRECTION: [f64; 3] = [0.8566, 0.3721, 0.3575];
+
+/// Volume enclosed by a closed (watertight) triangle mesh. Containment is decided by
+/// the parity of ray/triangle intersections, accelerated by a BVH over the triangles
+pub struct TriangleMesh {
+    vertices: Vec<DVec3>,
+    center: DVec3,
+    triangles: Vec<[usize; 3]>,
+    /// How far the volume is grown out from each triangle
+    triangle_margins: Vec<f64>,
+    bvh: Bvh,
+    bounding_box: BoundingBox,
+    object_id: u32,
+}
+
+impl TriangleMesh {
+    pub fn new(vertices: Vec<DVec3>, triangles: Vec<[usize; 3]>, object_id: u32) -> Self {
+        let triangle_boxes: Vec<BoundingBox> = triangles
+            .iter()
+            .map(|triangle| triangle.iter().map(|&i| vertices[i]).collect())
+            .collect();
+
+        let bounding_box = vertices.iter().copied().collect();
+        let center = vertices.iter().sum::<DVec3>() / vertices.len() as f64;
+
+        TriangleMesh {
+            center,
+            bvh: Bvh::new(&triangle_boxes),
+            vertices,
+            triangle_margins: vec![0.0; triangles.len()],
+            triangles,
+            bounding_box,
+            object_id,
+        }
+    }
+
+    /// Grows the mesh out from each triangle by the margin for the mesh's half-width along the
+    /// triangle's normal. Scaling the mesh about its center instead would pull surfaces inward
+    /// where the mesh is not star-shaped around the center
+    pub fn inflated(self, tolerance: &Tolerance) -> Self {
+        // Meshes without volume are not grown
+        let Some(hull) = ConvexHull::try_new(&self.vertices, get_epsilon(&self.vertices)) else {
+            return self;
+        };
+
+        let normals: Vec<DVec3> = self
+            .triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|i| self.vertices[i]);
+                (b - a).cross(&(c - a))
+            })
+            .map(|normal| {
+                if normal.magnitude() > 0.0 {
+                    normal.normalize()
+                } else {
+                    normal
+                }
+            })
+            .collect();
+
+        // Degenerate triangles have no normal, and are not grown
+        let triangle_margins = normals
+            .iter()
+            .zip(hull.get_widths(&normals))
+            .map(|(normal, width)| {
+                if normal.magnitude() > 0.0 {
+                    tolerance.get_margin(width / 2.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        TriangleMesh {
+            triangle_margins,
+            ..self
+        }
+    }
+
+    fn get_max_margin(&self) -> f64 {
+        self.triangle_margins.iter().copied().fold(0.0, f64::max)
+    }
+
+    fn is_inside_exact_mesh(&self, point: &DVec3) -> bool {
+        if !self.bounding_box.contains_point(point) {
+            return false;
+        }
+
+        let direction = vec3(RAY_DIRECTION[0], RAY_DIRECTION[1], RAY_DIRECTION[2]);
+        let inverse_direction = vec3(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+
+        let mut num_hits = 0;
+        self.bvh
+            .for_each_hit_by_ray(point, &inverse_direction, |triangle_index| {
+                if self.ray_hits_triangle(point, &direction, &self.triangles[triangle_index]) {
+                    num_hits += 1;
+                }
+            });
+
+        num_hits % 2 == 1
+    }
+
+    fn get_triangle_area(&self, triangle: &[usize; 3]) -> f64 {
+        let [a, b, c] = triangle.map(|i| self.vertices[i]);
+        (b - a).cross(&(c - a)).magnitude() / 2.0
+    }
+
+    /// Möller-Trumbore ray/triangle intersection, only counting hits in front of the origin
+    fn ray_hits_triangle(&self, origin: &DVec3, direction: &DVec3, triangle: &[usize; 3]) -> bool {
+        let v0 = self.vertices[triangle[0]];
+        let edge_1 = self.vertices[triangle[1]] - v0;
+        let edge_2 = self.vertices[triangle[2]] - v0;
+
+        let p = direction.cross(&edge_2);
+        let determinant = dot(&edge_1, &p);
+        if determinant.abs() < f64::EPSILON {
+            return false;
+        }
+
+        let inverse_determinant = 1.0 / determinant;
+        let to_origin = origin - v0;
+
+        let u = dot(&to_origin, &p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return false;
+        }
+
+        let q = to_origin.cross(&edge_1);
+        let v = dot(direction, &q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return false;
+        }
+
+        dot(&edge_2, &q) * inverse_determinant > 0.0
+    }
+}
+
+/// Distance to the closest point of the triangle, found by checking which vertex, edge or
+/// the interior of the triangle is closest (Ericson, Real-Time Collision Detection, 5.1.5)
+fn distance_to_triangle(point: &DVec3, a: &DVec3, b: &DVec3, c: &DVec3) -> f64 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = dot(&ab, &ap);
+    let d2 = dot(&ac, &ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return ap.magnitude();
+    }
+
+    let bp = point - b;
+    let d3 = dot(&ab, &bp);
+    let d4 = dot(&ac, &bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return bp.magnitude();
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return (ap - ab * (d1 / (d1 - d3))).magnitude();
+    }
+
+    let cp = point - c;
+    let d5 = dot(&ab, &cp);
+    let d6 = dot(&ac, &cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return cp.magnitude();
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return (ap - ac * (d2 / (d2 - d6))).magnitude();
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (bp - (c - b) * t).magnitude();
+    }
+
+    let denominator = 1.0 / (va + vb + vc);
+    (ap - ab * (vb * denominator) - ac * (vc * denominator)).magnitude()
+}
+
+impl Shape for TriangleMesh {
+    /// Points of the exact mesh closer than a negative margin to a triangle are left out, and
+    /// points outside it closer than a positive margin are taken in
+    fn contains_point(&self, point: &DVec3) -> bool {
+        let is_inside = self.is_inside_exact_mesh(point);
+        let max_abs_margin = self
+            .triangle_margins
+            .iter()
+            .fold(0.0, |max, margin| margin.abs().max(max));
+        if max_abs_margin == 0.0 {
+            return is_inside;
+        }
+
+        let query_box = BoundingBox {
+            min: point.add_scalar(-max_abs_margin),
+            max: point.add_scalar(max_abs_margin),
+        };
+        let mut nearby_triangles = vec![];
+        self.bvh.for_each_overlapping(&query_box, |triangle_index| {
+            nearby_triangles.push(triangle_index)
+        });
+        let mut distances = nearby_triangles.into_iter().map(|triangle_index| {
+            let [a, b, c] = self.triangles[triangle_index].map(|i| self.vertices[i]);
+            (
+                distance_to_triangle(point, &a, &b, &c),
+                self.triangle_margins[triangle_index],
+            )
+        });
+
+        if is_inside {
+            distances.all(|(distance, margin)| margin >= 0.0 || distance >= -margin)
+        } else {
+            distances.any(|(distance, margin)| distance < margin)
+        }
+    }
+
+    fn create_bounding_box(&self) -> BoundingBox {
+        let max_margin = self.get_max_margin();
+        BoundingBox {
+            min: self.bounding_box.min.add_scalar(-max_margin),
+            max: self.bounding_box.max.add_scalar(max_margin),
+        }
+    }
+
+    fn get_volume(&self) -> f64 {
+        let signed_volume: f64 = self
+            .triangles
+            .iter()
+            .map(|t| {
+                let (a, b, c) = (
+                    self.vertices[t[0]],
+                    self.vertices[t[1]],
+                    self.vertices[t[2]],
+                );
+                dot(&a, &b.cross(&c)) / 6.0
+            })
+            .sum();
+
+        // Each triangle grows the volume by its area times its margin
+        let margin_volume: f64 = self
+            .triangles
+            .iter()
+            .zip(&self.triangle_margins)
+            .map(|(triangle, margin)| self.get_triangle_area(triangle) * margin)
+            .sum();
+
+        (signed_volume.abs() + margin_volume).max(0.0)
+    }
+
+    fn get_distance_to_center(&self, point: &DVec3) -> f64 {
+        (point - self.center).magnitude()
+    }
+
+    fn get_object_id(&self) -> u32 {
+        self.object_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra_glm::{vec3, DVec3};
+
+    use super::TriangleMesh;
+    use crate::shapes::{Shape, Tolerance};
+
+    fn create_cube_mesh(min: DVec3, max: DVec3) -> TriangleMesh {
+        let vertices = (0..8)
+            .map(|i| {
+                vec3(
+                    if i & 1 == 0 { min.x } else { max.x },
+                    if i & 2 == 0 { min.y } else { max.y },
+                    if i & 4 == 0 { min.z } else { max.z },
+                )
+            })
+            .collect();
+
+        let triangles = vec![
+            [0, 2, 1],
+            [1, 2, 3],
+            [4, 5, 6],
+            [5, 7, 6],
+            [0, 1, 4],
+            [1, 5, 4],
+            [2, 6, 3],
+            [3, 6, 7],
+            [0, 4, 2],
+            [2, 4, 6],
+            [1, 3, 5],
+            [3, 7, 5],
+        ];
+
+        TriangleMesh::new(vertices, triangles, 0)
+    }
+
+    #[test]
+    fn cube_mesh_contains_interior_points_only() {
+        let cube = create_cube_mesh(vec3(-1.0, -1.0, -1.0), vec3(1.0, 1.0, 1.0));
+
+        assert!(cube.contains_point(&vec3(0.0, 0.0, 0.0)));
+        assert!(cube.contains_point(&vec3(0.9, -0.9, 0.5)));
+        assert!(!cube.contains_point(&vec3(1.1, 0.0, 0.0)));
+        assert!(!cube.contains_point(&vec3(-3.0, -3.0, -3.0)));
+    }
+
+    #[test]
+    fn finely_tessellated_sphere_mesh_matches_analytic_sphere() {
+        const RINGS: usize = 24;
+        const SEGMENTS: usize = 48;
+
+        let mut vertices = vec![vec3(0.0, 0.0, 1.0)];
+        for ring in 1..RINGS {
+            let theta = std::f64::consts::PI * ring as f64 / RINGS as f64;
+            for segment in 0..SEGMENTS {
+                let phi = std::f64::consts::TAU * segment as f64 / SEGMENTS as f64;
+                vertices.push(vec3(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ));
+            }
+        }
+        vertices.push(vec3(0.0, 0.0, -1.0));
+        let bottom = vertices.len() - 1;
+
+        let ring_vertex =
+            |ring: usize, segment: usize| 1 + (ring - 1) * SEGMENTS + segment % SEGMENTS;
+        let mut triangles = vec![];
+        for segment in 0..SEGMENTS {
+            triangles.push([0, ring_vertex(1, segment), ring_vertex(1, segment + 1)]);
+            triangles.push([
+                bottom,
+                ring_vertex(RINGS - 1, segment + 1),
+                ring_vertex(RINGS - 1, segment),
+            ]);
+            for ring in 1..(RINGS - 1) {
+                let (a, b) = (ring_vertex(ring, segment), ring_vertex(ring, segment + 1));
+                let (c, d) = (
+                    ring_vertex(ring + 1, segment),
+                    ring_vertex(ring + 1, segment + 1),
+                );
+                triangles.push([a, c, b]);
+                triangles.push([b, c, d]);
+            }
+        }
+
+        let sphere = TriangleMesh::new(vertices, triangles, 0);
+
+        for i in 0..200 {
+            let t = i as f64 * 0.61803;
+            let direction =
+                vec3(t.sin() * (3.0 * t).cos(), (2.0 * t).cos(), t.cos() * 0.7).normalize();
+            assert!(sphere.contains_point(&(direction * 0.95)));
+            assert!(!sphere.contains_point(&(direction * 1.05)));
+        }
+    }
+
+    #[test]
+    fn inflated_mesh_grows_out_from_its_triangles() {
+        let tolerance = Tolerance {
+            relative_factor: None,
+            absolute_margin: Some(0.5),
+        };
+        let cube = create_cube_mesh(vec3(1.0, 1.0, 1.0), vec3(3.0, 3.0, 3.0)).inflated(&tolerance);
+
+        assert!(cube.contains_point(&vec3(3.4, 2.0, 2.0)));
+        assert!(!cube.contains_point(&vec3(3.6, 2.0, 2.0)));
+        // Corners are rounded off
+        assert!(!cube.contains_point(&vec3(3.4, 3.4, 3.4)));
+        assert!((cube.create_bounding_box().max - vec3(3.5, 3.5, 3.5)).magnitude() < 1e-9);
+    }
+
+    #[test]
+    fn inflated_mesh_growth_is_capped_by_absolute_margin() {
+        let exact_beam = create_cube_mesh(vec3(0.0, 0.0, 0.0), vec3(10.0, 0.2, 0.2));
+        let interior_points = [vec3(9.99, 0.1, 0.1), vec3(5.0, 0.19, 0.01)];
+        assert!(interior_points
+            .iter()
+            .all(|point| exact_beam.contains_point(point)));
+
+        let beam = exact_beam.inflated(&Tolerance::default());
+
+        assert!(beam.contains_point(&vec3(10.05, 0.1, 0.1)));
+        assert!(!beam.contains_point(&vec3(10.5, 0.1, 0.1)));
+        assert!(interior_points
+            .iter()
+            .all(|point| beam.contains_point(point)));
+    }
+
+    #[test]
+    fn inflated_mesh_keeps_points_far_from_its_center() {
+        // Two separate cubes, so that the center of the vertices is outside the mesh
+        let left = create_cube_mesh(vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0));
+        let right = create_cube_mesh(vec3(9.0, 0.0, 0.0), vec3(10.0, 1.0, 1.0));
+        let vertices = [left.vertices, right.vertices].concat();
+        let triangles = [
+            left.triangles,
+            right.triangles.iter().map(|t| t.map(|i| i + 8)).collect(),
+        ]
+        .concat();
+        let mesh = TriangleMesh::new(vertices, triangles, 0).inflated(&Tolerance::default());
+
+        assert!(mesh.contains_point(&vec3(0.97, 0.5, 0.5)));
+        assert!(mesh.contains_point(&vec3(9.03, 0.5, 0.5)));
+        assert!(mesh.contains_point(&vec3(1.05, 0.5, 0.5)));
+        assert!(!mesh.contains_point(&vec3(5.0, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn cube_mesh_volume_is_exact() {
+        let cube = create_cube_mesh(vec3(1.0, 1.0, 1.0), vec3(3.0, 4.0, 5.0));
+        assert!((cube.get_volume() - 24.0).abs() < 1e-9);
+    }
+}
@@ -8,6 +8,7 @@ import { Box3, Vector3 } from 'three';
 
 import { EptBinaryLoader } from '../loading/EptBinaryLoader';
 import type { EptJson, EptSchemaEntry } from '../loading/EptJson';
+import type { EptDataType } from '../workers/types';
 
 import type { PointCloudEptGeometryNode } from './PointCloudEptGeometryNode';
 import type { IPointCloudTreeGeometry } from './IPointCloudTreeGeometry';
@@ -38,6 +39,7 @@ export class PointCloudEptGeometry implements IPointCloudTreeGeometry {
   private readonly _spacing: number;
 
   private readonly _schema: EptSchemaEntry[];
+  private readonly _dataType: EptDataType;
 
   private _root: PointCloudEptGeometryNode | undefined;
 
@@ -77,6 +79,10 @@ export class PointCloudEptGeometry implements IPointCloudTreeGeometry {
     return this._schema;
   }
 
+  get dataType(): EptDataType {
+    return this._dataType;
+  }
+
   get eptScale(): Vector3 {
     return this._eptScale;
   }
@@ -86,7 +92,7 @@ export class PointCloudEptGeometry implements IPointCloudTreeGeometry {
   }
 
   constructor(url: string, info: EptJson, dataLoader: ModelDataProvider, stylableObjects: StylableObject[]) {
-    if (info.dataType !== 'binary') {
+    if (!['binary', 'laszip', 'zstandard'].includes(info.dataType)) {
       throw new Error('Could not read data type: ' + info.dataType);
     }
 
@@ -103,6 +109,7 @@ export class PointCloudEptGeometry implements IPointCloudTreeGeometry {
     this._url = url;
 
     this._schema = schema;
+    this._dataType = info.dataType;
     this._span = info.span || info.ticks;
     this._boundingBox = toBox3(bounds);
     this._tightBoundingBox = toBox3(boundsConforming);
@@ -110,7 +117,7 @@ export class PointCloudEptGeometry implements IPointCloudTreeGeometry {
 
     this._spacing = (this._boundingBox.max.x - this._boundingBox.min.x) / this._span;
 
-    this._loader = new EptBinaryLoader(dataLoader, stylableObjects);
+    this._loader = new EptBinaryLoader(dataLoader, stylableObjects, info.dataType);
   }
 
   dispose(): void {}
@@ -16,9 +16,7 @@ import EptDecoderWorker from '../workers/eptBinaryDecoder.worker?worker&inline';
 
 import * as Comlink from 'comlink';
 
-import type { ParsedEptData, EptInputData } from '../workers/types';
-
-import { decomposeStylableObjects } from '../../decomposeStylableObjects';
+import type { ParsedEptData, EptInputData, EptDataType } from '../workers/types';
 
 import { fromThreeVector3 } from '@reveal/utilities';
 import { MetricsLogger } from '@reveal/metrics';
@@ -27,16 +25,25 @@ import type { EptBinaryDecoderWorker } from '../workers/eptBinaryDecoder.worker'
 export class EptBinaryLoader implements ILoader {
   private readonly _dataLoader: ModelDataProvider;
   private readonly _stylableObjectsWithBox: [SerializableStylableObject, Box3][];
+  private readonly _dataType: EptDataType;
 
   static readonly WORKER_POOL: WorkerPool<Worker> = new WorkerPool(8, EptDecoderWorker);
 
   extension(): string {
-    return '.bin';
+    switch (this._dataType) {
+      case 'laszip':
+        return '.laz';
+      case 'zstandard':
+        return '.zst';
+      default:
+        return '.bin';
+    }
   }
 
-  constructor(dataLoader: ModelDataProvider, stylableObjects: StylableObject[]) {
+  constructor(dataLoader: ModelDataProvider, stylableObjects: StylableObject[], dataType: EptDataType = 'binary') {
     this._dataLoader = dataLoader;
-    this._stylableObjectsWithBox = decomposeStylableObjects(stylableObjects).map(obj => {
+    this._dataType = dataType;
+    this._stylableObjectsWithBox = stylableObjects.map(obj => {
       const serializableShape = obj.shape.getSerializableShape();
 
       const boundingBox = obj.shape.createBoundingBox();
@@ -126,6 +133,7 @@ export class EptBinaryLoader implements ILoader {
     const eptDecoderWorker = autoTerminatingWorker.getComlinkProxy<EptBinaryDecoderWorker>();
     const eptData: EptInputData = {
       buffer: data,
+      dataType: this._dataType,
       schema: node.ept.schema,
       scale: node.ept.eptScale.toArray(),
       offset: node.ept.eptOffset.toArray(),
@@ -183,7 +191,7 @@ function createGeometryFromEptData(data: ParsedEptData): BufferGeometry {
   addAttributeIfPresent(Uint8Array, 'color', 4, data.color, true);
   addAttributeIfPresent(Float32Array, 'intensity', 1, data.intensity);
   addAttributeIfPresent(Uint8Array, 'classification', 1, data.classification);
-  addAttributeIfPresent(Uint16Array, 'objectId', 1, data.objectId);
+  addAttributeIfPresent(Uint32Array, 'objectId', 1, data.objectId);
 
   (geometry.attributes.indices as BufferAttribute).normalized = true;
 
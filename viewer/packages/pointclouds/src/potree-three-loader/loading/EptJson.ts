@@ -2,6 +2,8 @@
  * Copyright 2022 Cognite AS
  */
 
+import type { EptDataType } from '../workers/types';
+
 export type EptSchemaEntry = {
   name: string;
   type: 'signed' | 'unsigned' | 'float';
@@ -24,5 +26,5 @@ export type EptJson = {
   ticks: number;
   srs?: SpatialReferenceSystem;
   span?: number;
-  dataType: 'binary' | 'laszip';
+  dataType: EptDataType;
 };
@@ -3,20 +3,30 @@
  */
 
 import type { SerializableStylableObject } from '@reveal/data-providers';
-import { type SerializableCylinder, type SerializableBox } from '@reveal/utilities';
+import {
+  type AABB,
+  type Vec3,
+  type ISerializableShape,
+  type SerializableCylinder,
+  type SerializableBox,
+  type SerializableCompositeShape
+} from '@reveal/utilities';
 import { assertNever } from '@reveal/utilities/assertNever';
 import { ShapeType } from '@reveal/utilities/ISerializableShape';
 
-import type { WasmSerializedPointCloudObject } from '../../../wasm';
-import { assignPoints } from '../../../wasm';
-import type { Vector3, Box3 } from 'three';
+import type { WasmDecodedPoints, WasmSerializedPointCloudObject, WasmSerializedShape } from '../../../wasm';
+import {
+  decodeEptBinaryAndAssignPoints,
+  decodeEptZstandardAndAssignPoints,
+  decodeLazAndAssignPoints
+} from '../../../wasm';
+import type { EptInputData } from './types';
 
-function createWasmSerializedObject(obj: SerializableStylableObject): WasmSerializedPointCloudObject {
-  switch (obj.shape.shapeType) {
+function createWasmSerializedShape(shape: ISerializableShape): WasmSerializedShape {
+  switch (shape.shapeType) {
     case ShapeType.Cylinder: {
-      const cylinder = obj.shape as SerializableCylinder;
+      const cylinder = shape as SerializableCylinder;
       return {
-        object_id: obj.objectId,
         cylinder: {
           center_a: cylinder.centerA,
           center_b: cylinder.centerB,
@@ -25,37 +35,49 @@ function createWasmSerializedObject(obj: SerializableStylableObject): WasmSerial
       };
     }
     case ShapeType.Box: {
-      const box = obj.shape as SerializableBox;
+      const box = shape as SerializableBox;
       return {
-        object_id: obj.objectId,
         oriented_box: {
           inv_instance_matrix: box.invMatrix.data
         }
       };
     }
     case ShapeType.Composite: {
-      throw Error('Cannot assign points to objects - encountered unexpected composite object');
+      const composite = shape as SerializableCompositeShape;
+      return {
+        composite: {
+          operation: 'union',
+          children: composite.innerShapes.map(innerShape => createWasmSerializedShape(innerShape))
+        }
+      };
     }
     default:
-      assertNever(obj.shape.shapeType);
+      assertNever(shape.shapeType);
   }
 }
 
-export async function assignPointsToObjectsWithWasm(
-  points: Float32Array,
+function createWasmSerializedObject(obj: SerializableStylableObject): WasmSerializedPointCloudObject {
+  return { object_id: obj.objectId, ...createWasmSerializedShape(obj.shape) };
+}
+
+export async function decodeEptAndAssignPointsWithWasm(
+  data: EptInputData,
   objects: SerializableStylableObject[],
-  pointOffset: Vector3,
-  sectorBoundingBox: Box3
-): Promise<Uint16Array<ArrayBuffer>> {
+  pointOffset: Vec3,
+  sectorBoundingBox: AABB
+): Promise<WasmDecodedPoints> {
   const wasmShapes = objects.map(obj => createWasmSerializedObject(obj));
 
   try {
-    return await assignPoints(
-      wasmShapes,
-      points,
-      { min: sectorBoundingBox.min.toArray(), max: sectorBoundingBox.max.toArray() },
-      pointOffset.toArray()
-    );
+    if (data.dataType === 'laszip') {
+      return await decodeLazAndAssignPoints(data.buffer, data.mins, wasmShapes, sectorBoundingBox, pointOffset);
+    }
+
+    const layout = { schema: data.schema, scale: data.scale, offset: data.offset, mins: data.mins };
+    if (data.dataType === 'zstandard') {
+      return await decodeEptZstandardAndAssignPoints(data.buffer, layout, wasmShapes, sectorBoundingBox, pointOffset);
+    }
+    return await decodeEptBinaryAndAssignPoints(data.buffer, layout, wasmShapes, sectorBoundingBox, pointOffset);
   } catch (errorMessage: any) {
     return Promise.reject(new Error(errorMessage as string));
   }
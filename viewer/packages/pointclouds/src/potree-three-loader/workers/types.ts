@@ -16,6 +16,7 @@ export type ParsedEptData = {
   numberOfReturns: ArrayBuffer | undefined;
   pointSourceId: ArrayBuffer | undefined;
   indices: ArrayBuffer;
+  // Uint32Array of the object id assigned to each point
   objectId: ArrayBuffer;
 };
 
@@ -25,8 +26,11 @@ export type AttributeSchema = {
   type: 'signed' | 'unsigned' | 'float';
 };
 
+export type EptDataType = 'binary' | 'laszip' | 'zstandard';
+
 export type EptInputData = {
   buffer: ArrayBuffer;
+  dataType: EptDataType;
   schema: AttributeSchema[];
   scale: Vec3;
   offset: Vec3;
@@ -2,7 +2,24 @@
  * Copyright 2022 Cognite AS
  */
 
-import init, { assign_points } from './pkg/pointclouds_wasm';
+import init, {
+  assign_points,
+  assign_points_u32,
+  assign_points_with_distances,
+  assign_points_with_statistics,
+  assign_points_multi_label,
+  decode_ept_binary_and_assign_points,
+  decode_ept_zstandard_and_assign_points,
+  decode_laz_and_assign_points,
+  decode_las_and_assign_points,
+  decode_ply_and_assign_points,
+  read_las_bounding_box,
+  type DecodedPointCloud,
+  PointOctreeHandle,
+  CopcReaderHandle
+} from './pkg/pointclouds_wasm';
+
+export type { PointOctreeHandle, CopcReaderHandle };
 
 import type { AABB, Vec3 } from '@reveal/utilities';
 
@@ -14,21 +31,121 @@ export type WasmSerializedCylinder = {
   radius: number;
 };
 
+export type WasmSerializedCone = {
+  center_a: Vec3;
+  center_b: Vec3;
+  radius_a: number;
+  radius_b: number;
+};
+
 export type WasmSerializedOrientedBox = {
   inv_instance_matrix: number[];
 };
 
-export type WasmSerializedPointCloudObject = {
-  object_id: number;
+export type WasmSerializedEllipsoid = {
+  inv_instance_matrix: number[];
+};
+
+export type WasmSerializedSphere = {
+  center: Vec3;
+  radius: number;
+};
+
+export type WasmSerializedTorusSegment = {
+  center: Vec3;
+  axis: Vec3;
+  reference_direction: Vec3;
+  major_radius: number;
+  minor_radius: number;
+  start_angle: number;
+  end_angle: number;
+};
+
+export type WasmSerializedCapsule = {
+  center_a: Vec3;
+  center_b: Vec3;
+  radius: number;
+};
+
+export type WasmSerializedPipeRun = {
+  centers: Vec3[];
+  radius: number;
+};
+
+export type WasmSerializedHalfSpace = {
+  normal: Vec3;
+  offset: number;
+};
+
+// Either `half_spaces` (with outward-pointing normals) or `vertices` must be set
+export type WasmSerializedConvexPolyhedron = {
+  half_spaces?: WasmSerializedHalfSpace[] | undefined;
+  vertices?: Vec3[] | undefined;
+};
+
+// Vertices are projected onto the plane orthogonal to `axis`. The extrusion covers points
+// whose coordinate along the (normalized) axis is between `min_height` and `max_height`
+export type WasmSerializedPrism = {
+  vertices: Vec3[];
+  axis: Vec3;
+  min_height: number;
+  max_height: number;
+};
+
+// Closed mesh given by flat xyz vertex positions and triangle vertex indices
+export type WasmSerializedTriangleMesh = {
+  vertices: number[];
+  indices: number[];
+};
+
+// `difference` removes all subsequent children from the first one. The removed children are not
+// inflated by the inherited tolerance, since that would shrink the result, unless they set their own
+export type WasmSerializedCompositeOperation = 'union' | 'intersection' | 'difference';
+
+export type WasmSerializedComposite = {
+  operation: WasmSerializedCompositeOperation;
+  children: WasmSerializedShape[];
+};
+
+// Each extent of a shape (radius, half-size along a box axis, half-width of a polyhedron, prism or
+// mesh along a face's normal) is scaled by `relative_factor`, but grown by at most `absolute_margin` metres.
+// Leaving out one applies only the other. Defaults to 1.15 capped at 0.06 metres
+export type WasmTolerance = {
+  relative_factor?: number | undefined;
+  absolute_margin?: number | undefined;
+};
+
+export type WasmSerializedShape = {
+  // Overrides the default tolerance of the call, or that of the parent composite
+  tolerance?: WasmTolerance | undefined;
   cylinder?: WasmSerializedCylinder | undefined;
   oriented_box?: WasmSerializedOrientedBox | undefined;
+  sphere?: WasmSerializedSphere | undefined;
+  cone?: WasmSerializedCone | undefined;
+  torus_segment?: WasmSerializedTorusSegment | undefined;
+  capsule?: WasmSerializedCapsule | undefined;
+  pipe_run?: WasmSerializedPipeRun | undefined;
+  convex_polyhedron?: WasmSerializedConvexPolyhedron | undefined;
+  prism?: WasmSerializedPrism | undefined;
+  triangle_mesh?: WasmSerializedTriangleMesh | undefined;
+  composite?: WasmSerializedComposite | undefined;
+  ellipsoid?: WasmSerializedEllipsoid | undefined;
 };
 
+export type WasmSerializedPointCloudObject = WasmSerializedShape & {
+  object_id: number;
+};
+
+// Decides which object a point gets when it is inside several shapes. Defaults to 'last_wins'
+export type WasmOverlapResolution = 'first_wins' | 'last_wins' | 'smallest_volume_wins' | 'closest_to_center_wins';
+
 export async function assignPoints(
   input_shapes: Array<WasmSerializedPointCloudObject>,
   input_points: Float32Array,
   input_bounding_box: AABB,
-  input_point_offset: Vec3
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance
 ): Promise<Uint16Array<ArrayBuffer>> {
   return init().then(
     () =>
@@ -36,7 +153,410 @@ export async function assignPoints(
         input_shapes,
         input_points,
         input_bounding_box,
-        new Float64Array(input_point_offset)
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
       ) as Uint16Array<ArrayBuffer>
   );
 }
+
+// Like `assignPoints`, but supports object ids that do not fit in 16 bits
+export async function assignPointsU32(
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_points: Float32Array,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance
+): Promise<Uint32Array<ArrayBuffer>> {
+  return init().then(
+    () =>
+      assign_points_u32(
+        input_shapes,
+        input_points,
+        input_bounding_box,
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
+      ) as Uint32Array<ArrayBuffer>
+  );
+}
+
+// `distances` holds the signed distance from each point to the surface of its assigned shape
+// as modelled, i.e. without tolerance. Negative inside, NaN for unassigned points and for shapes
+// that do not support distances (currently all but cylinders and oriented boxes)
+export type PointObjectIdsWithDistances = {
+  objectIds: Uint32Array<ArrayBuffer>;
+  distances: Float32Array<ArrayBuffer>;
+};
+
+export async function assignPointsWithDistances(
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_points: Float32Array,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance
+): Promise<PointObjectIdsWithDistances> {
+  return init().then(() => {
+    const assignment = assign_points_with_distances(
+      input_shapes,
+      input_points,
+      input_bounding_box,
+      new Float64Array(input_point_offset),
+      input_overlap_resolution,
+      input_default_tolerance
+    );
+    const result = {
+      objectIds: assignment.object_ids() as Uint32Array<ArrayBuffer>,
+      distances: assignment.distances() as Float32Array<ArrayBuffer>
+    };
+    assignment.free();
+    return result;
+  });
+}
+
+// Aggregate of the points assigned to one object, in the same coordinates as the shapes.
+// `mean_distance` is NaN when no point has a distance, see `PointObjectIdsWithDistances`
+export type ObjectPointStatistics = {
+  object_id: number;
+  point_count: number;
+  bounding_box: AABB;
+  centroid: Vec3;
+  mean_distance: number;
+};
+
+// `statistics` has one entry per object with at least one point, ordered by object id
+export type PointObjectStatistics = {
+  objectIds: Uint32Array<ArrayBuffer>;
+  statistics: ObjectPointStatistics[];
+};
+
+export async function assignPointsWithStatistics(
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_points: Float32Array,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance
+): Promise<PointObjectStatistics> {
+  return init().then(() => {
+    const assignment = assign_points_with_statistics(
+      input_shapes,
+      input_points,
+      input_bounding_box,
+      new Float64Array(input_point_offset),
+      input_overlap_resolution,
+      input_default_tolerance
+    );
+    const result = {
+      objectIds: assignment.object_ids() as Uint32Array<ArrayBuffer>,
+      statistics: assignment.statistics() as ObjectPointStatistics[]
+    };
+    assignment.free();
+    return result;
+  });
+}
+
+// The object ids of point `i` are `objectIds[offsets[i]..offsets[i + 1]]`
+export type PointObjectMemberships = {
+  offsets: Uint32Array<ArrayBuffer>;
+  objectIds: Uint32Array<ArrayBuffer>;
+};
+
+export async function assignPointsMultiLabel(
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_points: Float32Array,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_default_tolerance?: WasmTolerance
+): Promise<PointObjectMemberships> {
+  return init().then(() => {
+    const memberships = assign_points_multi_label(
+      input_shapes,
+      input_points,
+      input_bounding_box,
+      new Float64Array(input_point_offset),
+      input_default_tolerance
+    );
+    const result = {
+      offsets: memberships.offsets() as Uint32Array<ArrayBuffer>,
+      objectIds: memberships.object_ids() as Uint32Array<ArrayBuffer>
+    };
+    memberships.free();
+    return result;
+  });
+}
+
+export async function createPointOctree(
+  input_points: Float32Array,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3
+): Promise<PointOctreeHandle> {
+  return init().then(
+    () => new PointOctreeHandle(input_points, input_bounding_box, new Float64Array(input_point_offset))
+  );
+}
+
+export type WasmAttributeSchema = {
+  name: string;
+  size: number;
+  type: 'signed' | 'unsigned' | 'float';
+};
+
+// Decoded positions are `value * scale + offset - mins`
+export type WasmEptLayout = {
+  schema: WasmAttributeSchema[];
+  scale: Vec3;
+  offset: Vec3;
+  mins: Vec3;
+};
+
+// Decoded object ids are 32-bit by default. 16-bit ids take half the memory, but decoding
+// then fails if an object id does not fit
+export type WasmObjectIdWidth = 16 | 32;
+
+// Attributes missing from the tile are undefined. Positions are relative to `mins`,
+// and colors are rgba with alpha left at 0
+export type WasmDecodedPoints = {
+  numPoints: number;
+  tightBoundingBox: { min: number[]; max: number[] };
+  mean: number[];
+  position: Float32Array<ArrayBuffer>;
+  color: Uint8Array<ArrayBuffer> | undefined;
+  intensity: Float32Array<ArrayBuffer> | undefined;
+  classification: Uint8Array<ArrayBuffer> | undefined;
+  returnNumber: Uint8Array<ArrayBuffer> | undefined;
+  numberOfReturns: Uint8Array<ArrayBuffer> | undefined;
+  pointSourceId: Uint16Array<ArrayBuffer> | undefined;
+  gpsTime: Float64Array<ArrayBuffer> | undefined;
+  nearInfrared: Uint16Array<ArrayBuffer> | undefined;
+  normal: Float32Array<ArrayBuffer> | undefined;
+  scalarFields: Record<string, Float32Array<ArrayBuffer>>;
+  // The offset the decoded positions are relative to
+  pointOffset: Vec3;
+  objectId: Uint16Array<ArrayBuffer> | Uint32Array<ArrayBuffer>;
+};
+
+function extractDecodedPoints(decoded: DecodedPointCloud, objectIdWidth: WasmObjectIdWidth): WasmDecodedPoints {
+  const result = {
+    numPoints: decoded.num_points(),
+    tightBoundingBox: {
+      min: Array.from(decoded.tight_bounding_box_min()),
+      max: Array.from(decoded.tight_bounding_box_max())
+    },
+    mean: Array.from(decoded.mean()),
+    position: decoded.position() as Float32Array<ArrayBuffer>,
+    color: decoded.color() as Uint8Array<ArrayBuffer> | undefined,
+    intensity: decoded.intensity() as Float32Array<ArrayBuffer> | undefined,
+    classification: decoded.classification() as Uint8Array<ArrayBuffer> | undefined,
+    returnNumber: decoded.return_number() as Uint8Array<ArrayBuffer> | undefined,
+    numberOfReturns: decoded.number_of_returns() as Uint8Array<ArrayBuffer> | undefined,
+    pointSourceId: decoded.point_source_id() as Uint16Array<ArrayBuffer> | undefined,
+    gpsTime: decoded.gps_time() as Float64Array<ArrayBuffer> | undefined,
+    nearInfrared: decoded.near_infrared() as Uint16Array<ArrayBuffer> | undefined,
+    normal: decoded.normal() as Float32Array<ArrayBuffer> | undefined,
+    scalarFields: Object.fromEntries(
+      decoded.scalar_field_names().map(name => [name, decoded.scalar_field(name) as Float32Array<ArrayBuffer>])
+    ),
+    pointOffset: Array.from(decoded.point_offset()) as Vec3,
+    objectId:
+      objectIdWidth === 16
+        ? (decoded.object_ids_u16() as Uint16Array<ArrayBuffer>)
+        : (decoded.object_ids() as Uint32Array<ArrayBuffer>)
+  };
+  decoded.free();
+  return result;
+}
+
+// Decodes an uncompressed EPT binary tile and assigns the points to objects in the same call.
+// The point offset is that of the decoded positions, as in `assignPoints`
+export async function decodeEptBinaryAndAssignPoints(
+  input_buffer: ArrayBuffer,
+  input_layout: WasmEptLayout,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): Promise<WasmDecodedPoints> {
+  return init().then(() =>
+    extractDecodedPoints(
+      decode_ept_binary_and_assign_points(
+        new Uint8Array(input_buffer),
+        input_layout,
+        input_shapes,
+        input_bounding_box,
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
+      ),
+      object_id_width
+    )
+  );
+}
+
+// Decodes a zstd-compressed EPT tile, which holds the same records as an EPT binary tile,
+// and assigns the points to objects in the same call
+export async function decodeEptZstandardAndAssignPoints(
+  input_buffer: ArrayBuffer,
+  input_layout: WasmEptLayout,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): Promise<WasmDecodedPoints> {
+  return init().then(() =>
+    extractDecodedPoints(
+      decode_ept_zstandard_and_assign_points(
+        new Uint8Array(input_buffer),
+        input_layout,
+        input_shapes,
+        input_bounding_box,
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
+      ),
+      object_id_width
+    )
+  );
+}
+
+// Decodes the vertices of an ASCII or binary PLY file and assigns the points to objects in the
+// same call. Decoded positions are relative to the minimum vertex position, see `pointOffset`
+export async function decodePlyAndAssignPoints(
+  input_buffer: ArrayBuffer,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): Promise<WasmDecodedPoints> {
+  return init().then(() =>
+    extractDecodedPoints(
+      decode_ply_and_assign_points(
+        new Uint8Array(input_buffer),
+        input_shapes,
+        input_overlap_resolution,
+        input_default_tolerance
+      ),
+      object_id_width
+    )
+  );
+}
+
+// Bounding box of the points in a LAS or LAZ file, as given by its header. Its minimum is a
+// natural choice for the `input_mins` of `decodeLasAndAssignPoints`
+export async function readLasBoundingBox(input_buffer: ArrayBuffer): Promise<AABB> {
+  return init().then(() => read_las_bounding_box(new Uint8Array(input_buffer)) as AABB);
+}
+
+// Decodes an uncompressed LAS file, e.g. one dropped into the viewer, and assigns the points
+// to objects in the same call. Decoded positions are relative to `input_mins`
+export async function decodeLasAndAssignPoints(
+  input_buffer: ArrayBuffer,
+  input_mins: Vec3,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): Promise<WasmDecodedPoints> {
+  return init().then(() =>
+    extractDecodedPoints(
+      decode_las_and_assign_points(
+        new Uint8Array(input_buffer),
+        new Float64Array(input_mins),
+        input_shapes,
+        input_bounding_box,
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
+      ),
+      object_id_width
+    )
+  );
+}
+
+// Decodes a LAZ-compressed EPT tile and assigns the points to objects in the same call.
+// Decoded positions are relative to `input_mins`, as for binary tiles
+export async function decodeLazAndAssignPoints(
+  input_buffer: ArrayBuffer,
+  input_mins: Vec3,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): Promise<WasmDecodedPoints> {
+  return init().then(() =>
+    extractDecodedPoints(
+      decode_laz_and_assign_points(
+        new Uint8Array(input_buffer),
+        new Float64Array(input_mins),
+        input_shapes,
+        input_bounding_box,
+        new Float64Array(input_point_offset),
+        input_overlap_resolution,
+        input_default_tolerance
+      ),
+      object_id_width
+    )
+  );
+}
+
+// `key` is the depth-x-y-z name of the node, as for EPT. `point_count` is -1 for entries
+// pointing to a child hierarchy page, whose byte range should be parsed as another page
+export type WasmCopcHierarchyEntry = {
+  key: string;
+  offset: number;
+  byte_size: number;
+  point_count: number;
+  bounding_box: AABB;
+};
+
+// Creates a reader from the start of a COPC file, which must contain the header and all VLRs.
+// The hierarchy pages and point chunks are then fetched by byte range
+export async function createCopcReader(input_buffer: ArrayBuffer): Promise<CopcReaderHandle> {
+  return init().then(() => new CopcReaderHandle(new Uint8Array(input_buffer)));
+}
+
+export function parseCopcHierarchyPage(
+  reader: CopcReaderHandle,
+  input_page: ArrayBuffer
+): Array<WasmCopcHierarchyEntry> {
+  return reader.parse_hierarchy_page(new Uint8Array(input_page)) as Array<WasmCopcHierarchyEntry>;
+}
+
+// Decodes the point chunk of one COPC node and assigns the points to objects in the same call.
+// Decoded positions are relative to `input_mins`, as for EPT tiles
+export function decodeCopcChunkAndAssignPoints(
+  reader: CopcReaderHandle,
+  input_chunk: ArrayBuffer,
+  input_point_count: number,
+  input_mins: Vec3,
+  input_shapes: Array<WasmSerializedPointCloudObject>,
+  input_bounding_box: AABB,
+  input_point_offset: Vec3,
+  input_overlap_resolution?: WasmOverlapResolution,
+  input_default_tolerance?: WasmTolerance,
+  object_id_width: WasmObjectIdWidth = 32
+): WasmDecodedPoints {
+  return extractDecodedPoints(
+    reader.decode_chunk_and_assign_points(
+      new Uint8Array(input_chunk),
+      input_point_count,
+      new Float64Array(input_mins),
+      input_shapes,
+      input_bounding_box,
+      new Float64Array(input_point_offset),
+      input_overlap_resolution,
+      input_default_tolerance
+    ),
+    object_id_width
+  );
+}
@@ -2,64 +2,792 @@
  * Copyright 2022 Cognite AS
  */
 
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
 extern crate console_error_panic_hook;
 
-#[cfg(test)]
-mod test_setup {
-    use wasm_bindgen_test::wasm_bindgen_test_configure;
-
-    wasm_bindgen_test_configure!(run_in_browser);
-}
-
-mod linalg;
 mod parse_inputs;
-mod point_octree;
-mod shapes;
 
-use linalg::BoundingBox;
-use parse_inputs::InputBoundingBox;
+use pointclouds_core::linalg::BoundingBox;
+use pointclouds_core::{decoding, object_assignment, point_octree};
 
-fn init() -> () {
+fn init() {
     // This provides better error messages in debug mode.
     // It's disabled in release mode so it doesn't bloat up the file size.
     #[cfg(debug_assertions)]
     console_error_panic_hook::set_once();
 }
 
+/// Object memberships of each point, see `object_assignment::ObjectMemberships`
+#[wasm_bindgen]
+pub struct PointObjectMemberships {
+    memberships: object_assignment::ObjectMemberships,
+}
+
+#[wasm_bindgen]
+impl PointObjectMemberships {
+    /// Offsets into `object_ids` for each point, with one extra entry at the end
+    pub fn offsets(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.memberships.offsets[..])
+    }
+
+    pub fn object_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.memberships.object_ids[..])
+    }
+}
+
+/// Object id of each point, and its signed distance to the surface of the assigned shape,
+/// see `object_assignment::ObjectIdsWithDistances`
+#[wasm_bindgen]
+pub struct PointObjectIdsWithDistances {
+    result: object_assignment::ObjectIdsWithDistances,
+}
+
+#[wasm_bindgen]
+impl PointObjectIdsWithDistances {
+    pub fn object_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.result.object_ids[..])
+    }
+
+    /// Negative inside the shape. NaN for unassigned points and shapes that do not support it
+    pub fn distances(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.result.distances[..])
+    }
+}
+
+#[derive(Serialize)]
+struct OutputBoundingBox {
+    min: [f64; 3],
+    max: [f64; 3],
+}
+
+#[derive(Serialize)]
+struct OutputObjectStatistics {
+    object_id: u32,
+    point_count: u32,
+    bounding_box: OutputBoundingBox,
+    centroid: [f64; 3],
+    mean_distance: f64,
+}
+
+impl From<&object_assignment::ObjectStatistics> for OutputObjectStatistics {
+    fn from(statistics: &object_assignment::ObjectStatistics) -> OutputObjectStatistics {
+        OutputObjectStatistics {
+            object_id: statistics.object_id,
+            point_count: statistics.point_count,
+            bounding_box: OutputBoundingBox {
+                min: statistics.bounding_box.min.into(),
+                max: statistics.bounding_box.max.into(),
+            },
+            centroid: statistics.centroid.into(),
+            mean_distance: statistics.mean_distance,
+        }
+    }
+}
+
+/// Object id of each point, together with statistics for each object that got points,
+/// see `object_assignment::ObjectStatistics`
+#[wasm_bindgen]
+pub struct PointObjectStatistics {
+    object_ids: Vec<u32>,
+    statistics: Vec<object_assignment::ObjectStatistics>,
+}
+
+#[wasm_bindgen]
+impl PointObjectStatistics {
+    pub fn object_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.object_ids[..])
+    }
+
+    /// Array of `{ object_id, point_count, bounding_box, centroid, mean_distance }` objects
+    pub fn statistics(&self) -> Result<JsValue, String> {
+        let output: Vec<OutputObjectStatistics> = self.statistics.iter().map(Into::into).collect();
+
+        serde_wasm_bindgen::to_value(&output).map_err(|serde_error| {
+            format!("Got error while serializing statistics: {}", serde_error)
+        })
+    }
+}
+
+/// Point attributes decoded from a tile, see `decoding::DecodedPoints`, together with the
+/// object id assigned to each point
+#[wasm_bindgen]
+pub struct DecodedPointCloud {
+    points: decoding::DecodedPoints,
+    point_offset: Vec<f64>,
+    object_ids: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl DecodedPointCloud {
+    pub fn num_points(&self) -> u32 {
+        self.points.num_points() as u32
+    }
+
+    pub fn position(&self) -> js_sys::Float32Array {
+        js_sys::Float32Array::from(&self.points.position[..])
+    }
+
+    /// rgba for each point, with alpha left at 0
+    pub fn color(&self) -> Option<js_sys::Uint8Array> {
+        self.points.color.as_deref().map(js_sys::Uint8Array::from)
+    }
+
+    pub fn intensity(&self) -> Option<js_sys::Float32Array> {
+        self.points
+            .intensity
+            .as_deref()
+            .map(js_sys::Float32Array::from)
+    }
+
+    pub fn classification(&self) -> Option<js_sys::Uint8Array> {
+        self.points
+            .classification
+            .as_deref()
+            .map(js_sys::Uint8Array::from)
+    }
+
+    pub fn return_number(&self) -> Option<js_sys::Uint8Array> {
+        self.points
+            .return_number
+            .as_deref()
+            .map(js_sys::Uint8Array::from)
+    }
+
+    pub fn number_of_returns(&self) -> Option<js_sys::Uint8Array> {
+        self.points
+            .number_of_returns
+            .as_deref()
+            .map(js_sys::Uint8Array::from)
+    }
+
+    pub fn point_source_id(&self) -> Option<js_sys::Uint16Array> {
+        self.points
+            .point_source_id
+            .as_deref()
+            .map(js_sys::Uint16Array::from)
+    }
+
+    pub fn gps_time(&self) -> Option<js_sys::Float64Array> {
+        self.points
+            .gps_time
+            .as_deref()
+            .map(js_sys::Float64Array::from)
+    }
+
+    pub fn near_infrared(&self) -> Option<js_sys::Uint16Array> {
+        self.points
+            .near_infrared
+            .as_deref()
+            .map(js_sys::Uint16Array::from)
+    }
+
+    /// xyz for each point
+    pub fn normal(&self) -> Option<js_sys::Float32Array> {
+        self.points
+            .normal
+            .as_deref()
+            .map(js_sys::Float32Array::from)
+    }
+
+    pub fn scalar_field_names(&self) -> Vec<String> {
+        self.points
+            .scalar_fields
+            .iter()
+            .map(|field| field.name.clone())
+            .collect()
+    }
+
+    pub fn scalar_field(&self, name: &str) -> Option<js_sys::Float32Array> {
+        self.points
+            .scalar_fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| js_sys::Float32Array::from(&field.values[..]))
+    }
+
+    /// The offset the decoded positions are relative to
+    pub fn point_offset(&self) -> Vec<f64> {
+        self.point_offset.clone()
+    }
+
+    pub fn tight_bounding_box_min(&self) -> Vec<f64> {
+        self.points.tight_bounding_box.min.as_slice().to_vec()
+    }
+
+    pub fn tight_bounding_box_max(&self) -> Vec<f64> {
+        self.points.tight_bounding_box.max.as_slice().to_vec()
+    }
+
+    pub fn mean(&self) -> Vec<f64> {
+        self.points.mean().as_slice().to_vec()
+    }
+
+    pub fn object_ids(&self) -> js_sys::Uint32Array {
+        js_sys::Uint32Array::from(&self.object_ids[..])
+    }
+
+    /// Like `object_ids`, but takes half the memory. Fails if an object id does not fit in 16 bits
+    pub fn object_ids_u16(&self) -> Result<js_sys::Uint16Array, String> {
+        let object_ids = self
+            .object_ids
+            .iter()
+            .map(|&object_id| {
+                u16::try_from(object_id).map_err(|_| {
+                    format!(
+                        "Object id {} is too large for the output array, use 32-bit object ids",
+                        object_id
+                    )
+                })
+            })
+            .collect::<Result<Vec<u16>, String>>()?;
+
+        Ok(js_sys::Uint16Array::from(&object_ids[..]))
+    }
+}
+
+impl DecodedPointCloud {
+    /// Assigns the decoded points, whose positions are relative to the point offset, to objects
+    fn try_assign(
+        points: decoding::DecodedPoints,
+        input_objects: Vec<JsValue>,
+        input_bounding_box: js_sys::Object,
+        input_point_offset: Vec<f64>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<DecodedPointCloud, String> {
+        let bounding_box = parse_inputs::try_parse_bounding_box(input_bounding_box)?;
+
+        DecodedPointCloud::try_assign_in_box(
+            points,
+            input_objects,
+            bounding_box,
+            input_point_offset,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )
+    }
+
+    fn try_assign_in_box(
+        points: decoding::DecodedPoints,
+        input_objects: Vec<JsValue>,
+        bounding_box: BoundingBox,
+        input_point_offset: Vec<f64>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<DecodedPointCloud, String> {
+        let point_vec = parse_inputs::create_points(&points.position, input_point_offset.clone());
+        let octree_handle = PointOctreeHandle {
+            octree: point_octree::PointOctree::new(bounding_box, point_vec),
+        };
+
+        let object_ids = octree_handle.assign_object_ids(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )?;
+
+        Ok(DecodedPointCloud {
+            points,
+            point_offset: input_point_offset,
+            object_ids,
+        })
+    }
+}
+
+/// Point octree built once for a set of points (typically one EPT node), which can then be
+/// queried repeatedly from JavaScript without re-parsing the points
+#[wasm_bindgen]
+pub struct PointOctreeHandle {
+    octree: point_octree::PointOctree,
+}
+
+#[wasm_bindgen]
+impl PointOctreeHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        input_points: js_sys::Float32Array,
+        input_bounding_box: js_sys::Object,
+        input_point_offset: Vec<f64>,
+    ) -> Result<PointOctreeHandle, String> {
+        init();
+
+        let point_vec = parse_inputs::parse_points(&input_points, input_point_offset);
+        let bounding_box = parse_inputs::try_parse_bounding_box(input_bounding_box)?;
+
+        Ok(PointOctreeHandle {
+            octree: point_octree::PointOctree::new(bounding_box, point_vec),
+        })
+    }
+
+    pub fn num_points(&self) -> u32 {
+        self.octree.num_points() as u32
+    }
+
+    pub fn assign_points(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<js_sys::Uint16Array, String> {
+        let object_ids: Vec<u16> = self.assign_object_ids(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )?;
+        Ok(js_sys::Uint16Array::from(&object_ids[..]))
+    }
+
+    /// Like `assign_points`, but supports object ids that do not fit in 16 bits
+    pub fn assign_points_u32(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<js_sys::Uint32Array, String> {
+        let object_ids: Vec<u32> = self.assign_object_ids(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )?;
+        Ok(js_sys::Uint32Array::from(&object_ids[..]))
+    }
+
+    /// Like `assign_points_u32`, but also measures how far each point is from the surface of the
+    /// shape it is assigned to, as modelled without tolerance
+    pub fn assign_points_with_distances(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<PointObjectIdsWithDistances, String> {
+        let default_tolerance = parse_inputs::try_parse_tolerance(input_default_tolerance)?;
+        let exact_shape_vec = parse_inputs::try_parse_exact_objects(&input_objects)?;
+        let shape_vec = parse_inputs::try_parse_objects(input_objects, &default_tolerance)?;
+        let overlap_resolution =
+            parse_inputs::try_parse_overlap_resolution(input_overlap_resolution)?;
+
+        Ok(PointObjectIdsWithDistances {
+            result: object_assignment::assign_object_ids_with_distances(
+                &self.octree,
+                &shape_vec,
+                &exact_shape_vec,
+                overlap_resolution,
+            ),
+        })
+    }
+
+    /// Like `assign_points_u32`, but also aggregates the points assigned to each object
+    pub fn assign_points_with_statistics(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<PointObjectStatistics, String> {
+        let with_distances = self.assign_points_with_distances(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )?;
+        let statistics =
+            object_assignment::compute_object_statistics(&self.octree, &with_distances.result);
+
+        Ok(PointObjectStatistics {
+            object_ids: with_distances.result.object_ids,
+            statistics,
+        })
+    }
+
+    /// Sorted indices of the points inside the shape, which needs no object id
+    pub fn find_point_indices_in_shape(
+        &self,
+        input_shape: JsValue,
+        input_default_tolerance: JsValue,
+    ) -> Result<js_sys::Uint32Array, String> {
+        let default_tolerance = parse_inputs::try_parse_tolerance(input_default_tolerance)?;
+        let shape_vec = parse_inputs::try_parse_objects(vec![input_shape], &default_tolerance)?;
+
+        let point_indices = object_assignment::find_point_indices(
+            &self.octree,
+            shape_vec.iter().map(|shape| shape.as_ref()),
+        );
+        Ok(js_sys::Uint32Array::from(&point_indices[..]))
+    }
+
+    /// Sorted indices of the points inside any of the shapes with the given object id,
+    /// regardless of overlaps with other objects
+    pub fn find_point_indices_of_object(
+        &self,
+        input_objects: Vec<JsValue>,
+        object_id: u32,
+        input_default_tolerance: JsValue,
+    ) -> Result<js_sys::Uint32Array, String> {
+        let default_tolerance = parse_inputs::try_parse_tolerance(input_default_tolerance)?;
+        let shape_vec = parse_inputs::try_parse_objects(input_objects, &default_tolerance)?;
+
+        let point_indices =
+            object_assignment::find_object_point_indices(&self.octree, &shape_vec, object_id);
+        Ok(js_sys::Uint32Array::from(&point_indices[..]))
+    }
+
+    pub fn assign_points_multi_label(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_default_tolerance: JsValue,
+    ) -> Result<PointObjectMemberships, String> {
+        let default_tolerance = parse_inputs::try_parse_tolerance(input_default_tolerance)?;
+        let shape_vec = parse_inputs::try_parse_objects(input_objects, &default_tolerance)?;
+
+        Ok(PointObjectMemberships {
+            memberships: object_assignment::assign_all_object_ids(&self.octree, &shape_vec),
+        })
+    }
+}
+
+impl PointOctreeHandle {
+    fn assign_object_ids<T>(
+        &self,
+        input_objects: Vec<JsValue>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<Vec<T>, String>
+    where
+        T: Copy + Default + TryFrom<u32>,
+    {
+        let default_tolerance = parse_inputs::try_parse_tolerance(input_default_tolerance)?;
+        let shape_vec = parse_inputs::try_parse_objects(input_objects, &default_tolerance)?;
+        let overlap_resolution =
+            parse_inputs::try_parse_overlap_resolution(input_overlap_resolution)?;
+
+        object_assignment::assign_object_ids(&self.octree, &shape_vec, overlap_resolution)
+    }
+}
+
 #[wasm_bindgen]
 pub fn assign_points(
     input_objects: Vec<JsValue>,
     input_points: js_sys::Float32Array,
     input_bounding_box: js_sys::Object,
     input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
 ) -> Result<js_sys::Uint16Array, String> {
+    PointOctreeHandle::new(input_points, input_bounding_box, input_point_offset)?.assign_points(
+        input_objects,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+#[wasm_bindgen]
+pub fn assign_points_u32(
+    input_objects: Vec<JsValue>,
+    input_points: js_sys::Float32Array,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<js_sys::Uint32Array, String> {
+    PointOctreeHandle::new(input_points, input_bounding_box, input_point_offset)?.assign_points_u32(
+        input_objects,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+#[wasm_bindgen]
+pub fn assign_points_with_distances(
+    input_objects: Vec<JsValue>,
+    input_points: js_sys::Float32Array,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<PointObjectIdsWithDistances, String> {
+    PointOctreeHandle::new(input_points, input_bounding_box, input_point_offset)?
+        .assign_points_with_distances(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )
+}
+
+#[wasm_bindgen]
+pub fn assign_points_with_statistics(
+    input_objects: Vec<JsValue>,
+    input_points: js_sys::Float32Array,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<PointObjectStatistics, String> {
+    PointOctreeHandle::new(input_points, input_bounding_box, input_point_offset)?
+        .assign_points_with_statistics(
+            input_objects,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )
+}
+
+#[wasm_bindgen]
+pub fn assign_points_multi_label(
+    input_objects: Vec<JsValue>,
+    input_points: js_sys::Float32Array,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_default_tolerance: JsValue,
+) -> Result<PointObjectMemberships, String> {
+    PointOctreeHandle::new(input_points, input_bounding_box, input_point_offset)?
+        .assign_points_multi_label(input_objects, input_default_tolerance)
+}
+
+/// Decodes an uncompressed EPT binary tile, laid out as given by `input_layout`, and assigns
+/// the decoded points to objects like `assign_points`
+#[wasm_bindgen]
+pub fn decode_ept_binary_and_assign_points(
+    input_buffer: &[u8],
+    input_layout: JsValue,
+    input_objects: Vec<JsValue>,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<DecodedPointCloud, String> {
+    init();
+
+    let layout = parse_inputs::try_parse_ept_layout(input_layout)?;
+    let points = decoding::decode_ept_binary(input_buffer, &layout)?;
+
+    DecodedPointCloud::try_assign(
+        points,
+        input_objects,
+        input_bounding_box,
+        input_point_offset,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+/// Decodes a zstd-compressed EPT tile, which holds records laid out as given by `input_layout`,
+/// and assigns the decoded points to objects like `assign_points`
+#[wasm_bindgen]
+pub fn decode_ept_zstandard_and_assign_points(
+    input_buffer: &[u8],
+    input_layout: JsValue,
+    input_objects: Vec<JsValue>,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<DecodedPointCloud, String> {
     init();
 
-    let mut point_vec = parse_inputs::parse_points(&input_points, input_point_offset);
-    let bounding_box: BoundingBox =
-        serde_wasm_bindgen::from_value::<InputBoundingBox>(input_bounding_box.into())
-            .map_err(|serde_error| {
-                format!(
-                    "Got error while deserializing bounding box: {}",
-                    serde_error
-                )
-            })?
-            .into();
+    let layout = parse_inputs::try_parse_ept_layout(input_layout)?;
+    let points = decoding::decode_ept_zstandard(input_buffer, &layout)?;
+
+    DecodedPointCloud::try_assign(
+        points,
+        input_objects,
+        input_bounding_box,
+        input_point_offset,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+/// Decodes an uncompressed LAS file and assigns the decoded points to objects like
+/// `assign_points`. Positions are made relative to `input_mins`, like for EPT tiles
+#[wasm_bindgen]
+pub fn decode_las_and_assign_points(
+    input_buffer: &[u8],
+    input_mins: Vec<f64>,
+    input_objects: Vec<JsValue>,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<DecodedPointCloud, String> {
+    init();
+
+    let mins = parse_inputs::try_parse_mins(input_mins)?;
+    let points = decoding::decode_las(input_buffer, &mins)?;
+
+    DecodedPointCloud::try_assign(
+        points,
+        input_objects,
+        input_bounding_box,
+        input_point_offset,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+/// Decodes the vertices of an ASCII or binary PLY file and assigns the decoded points to objects
+/// like `assign_points`. PLY files have no bounds, so positions are made relative to the minimum
+/// vertex position, see `DecodedPointCloud::point_offset`
+#[wasm_bindgen]
+pub fn decode_ply_and_assign_points(
+    input_buffer: &[u8],
+    input_objects: Vec<JsValue>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<DecodedPointCloud, String> {
+    init();
+
+    let decoding::DecodedPly { points, mins } = decoding::decode_ply(input_buffer)?;
+    let bounding_box = BoundingBox {
+        min: points.tight_bounding_box.min + mins,
+        max: points.tight_bounding_box.max + mins,
+    };
+
+    DecodedPointCloud::try_assign_in_box(
+        points,
+        input_objects,
+        bounding_box,
+        mins.as_slice().to_vec(),
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+/// Bounding box of the points in a LAS or LAZ file, as given by its header
+#[wasm_bindgen]
+pub fn read_las_bounding_box(input_buffer: &[u8]) -> Result<JsValue, String> {
+    init();
+
+    let bounds = decoding::parse_las_header(input_buffer)?.bounding_box;
+    let output = OutputBoundingBox {
+        min: bounds.min.into(),
+        max: bounds.max.into(),
+    };
+
+    serde_wasm_bindgen::to_value(&output)
+        .map_err(|serde_error| format!("Got error while serializing bounding box: {}", serde_error))
+}
+
+/// Decodes a LAZ-compressed EPT tile and assigns the decoded points to objects like
+/// `assign_points`. Positions are made relative to `input_mins`, like for binary tiles
+#[wasm_bindgen]
+pub fn decode_laz_and_assign_points(
+    input_buffer: &[u8],
+    input_mins: Vec<f64>,
+    input_objects: Vec<JsValue>,
+    input_bounding_box: js_sys::Object,
+    input_point_offset: Vec<f64>,
+    input_overlap_resolution: Option<String>,
+    input_default_tolerance: JsValue,
+) -> Result<DecodedPointCloud, String> {
+    init();
+
+    let mins = parse_inputs::try_parse_mins(input_mins)?;
+    let points = decoding::decode_laz(input_buffer, &mins)?;
+
+    DecodedPointCloud::try_assign(
+        points,
+        input_objects,
+        input_bounding_box,
+        input_point_offset,
+        input_overlap_resolution,
+        input_default_tolerance,
+    )
+}
+
+#[derive(Serialize)]
+struct OutputCopcHierarchyEntry {
+    key: String,
+    offset: f64,
+    byte_size: u32,
+    point_count: i32,
+    bounding_box: OutputBoundingBox,
+}
+
+/// Reader for a COPC file, created from the header and VLRs. The hierarchy pages and point
+/// chunks are then fetched by byte range and passed in one at a time
+#[wasm_bindgen]
+pub struct CopcReaderHandle {
+    reader: decoding::CopcReader,
+}
+
+#[wasm_bindgen]
+impl CopcReaderHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new(input_buffer: &[u8]) -> Result<CopcReaderHandle, String> {
+        init();
+
+        Ok(CopcReaderHandle {
+            reader: decoding::CopcReader::try_new(input_buffer)?,
+        })
+    }
+
+    pub fn root_hierarchy_offset(&self) -> f64 {
+        self.reader.info().root_hierarchy_offset as f64
+    }
+
+    pub fn root_hierarchy_size(&self) -> f64 {
+        self.reader.info().root_hierarchy_size as f64
+    }
 
-    let shape_vec = parse_inputs::try_parse_objects(input_objects)?;
+    pub fn spacing(&self) -> f64 {
+        self.reader.info().spacing
+    }
 
-    let object_ids = js_sys::Uint16Array::new_with_length(input_points.length() / 3).fill(
-        0,
-        0,
-        input_points.length() / 3,
-    );
+    /// Array of `{ key, offset, byte_size, point_count, bounding_box }` objects, where `key` is
+    /// the depth-x-y-z name of the node and `point_count` is -1 for child hierarchy pages
+    pub fn parse_hierarchy_page(&self, input_page: &[u8]) -> Result<JsValue, String> {
+        let info = self.reader.info();
+        let output: Vec<OutputCopcHierarchyEntry> = self
+            .reader
+            .parse_hierarchy_page(input_page)?
+            .iter()
+            .map(|entry| {
+                let bounds = info.node_bounds(&entry.key);
+                OutputCopcHierarchyEntry {
+                    key: entry.key.name(),
+                    offset: entry.offset as f64,
+                    byte_size: entry.byte_size,
+                    point_count: entry.point_count,
+                    bounding_box: OutputBoundingBox {
+                        min: bounds.min.into(),
+                        max: bounds.max.into(),
+                    },
+                }
+            })
+            .collect();
 
-    let octree = point_octree::PointOctree::new(bounding_box, &mut point_vec);
+        serde_wasm_bindgen::to_value(&output).map_err(|serde_error| {
+            format!(
+                "Got error while serializing COPC hierarchy: {}",
+                serde_error
+            )
+        })
+    }
 
-    shape_vec.iter().for_each(|shape| {
-        octree.assign_object_ids(&shape.create_bounding_box(), shape, &object_ids);
-    });
+    /// Decodes the point chunk of one node and assigns the points to objects like
+    /// `assign_points`. Positions are made relative to `input_mins`
+    #[allow(clippy::too_many_arguments)]
+    pub fn decode_chunk_and_assign_points(
+        &self,
+        input_chunk: &[u8],
+        input_point_count: u32,
+        input_mins: Vec<f64>,
+        input_objects: Vec<JsValue>,
+        input_bounding_box: js_sys::Object,
+        input_point_offset: Vec<f64>,
+        input_overlap_resolution: Option<String>,
+        input_default_tolerance: JsValue,
+    ) -> Result<DecodedPointCloud, String> {
+        let mins = parse_inputs::try_parse_mins(input_mins)?;
+        let points = self
+            .reader
+            .decode_chunk(input_chunk, input_point_count, &mins)?;
 
-    Ok(object_ids)
+        DecodedPointCloud::try_assign(
+            points,
+            input_objects,
+            input_bounding_box,
+            input_point_offset,
+            input_overlap_resolution,
+            input_default_tolerance,
+        )
+    }
 }
@@ -1,58 +1,42 @@
-use nalgebra_glm::{scaling, vec3, DMat4};
+use nalgebra_glm::{vec3, DVec3};
 use std::vec::Vec;
 
-use crate::linalg::BoundingBox;
-use crate::linalg::Vec3WithIndex;
-use crate::shapes;
-
-use serde::Deserialize;
-
-#[derive(Debug, Deserialize)]
-struct InputCylinder {
-    center_a: [f64; 3],
-    center_b: [f64; 3],
-    radius: f64,
-}
-
-#[derive(Debug, Deserialize)]
-struct InputOrientedBox {
-    inv_instance_matrix: [f64; 16],
-}
-
-#[derive(Debug, Deserialize)]
-pub struct InputShape {
-    object_id: u16,
-    cylinder: Option<Box<InputCylinder>>,
-    oriented_box: Option<Box<InputOrientedBox>>,
-}
-
-#[derive(Deserialize)]
-pub struct InputBoundingBox {
-    pub min: [f64; 3],
-    pub max: [f64; 3],
-}
-
-impl Into<BoundingBox> for InputBoundingBox {
-    fn into(self) -> BoundingBox {
-        BoundingBox {
-            min: vec3(self.min[0], self.min[1], self.min[2]),
-            max: vec3(self.max[0], self.max[1], self.max[2]),
-        }
-    }
+use pointclouds_core::decoding;
+use pointclouds_core::inputs::{
+    self, InputBoundingBox, InputEptLayout, InputShape, InputTolerance,
+};
+use pointclouds_core::linalg::{BoundingBox, Vec3WithIndex};
+use pointclouds_core::shapes;
+
+pub use pointclouds_core::inputs::try_parse_overlap_resolution;
+
+pub fn try_parse_bounding_box(input_bounding_box: js_sys::Object) -> Result<BoundingBox, String> {
+    serde_wasm_bindgen::from_value::<InputBoundingBox>(input_bounding_box.into())
+        .map(|input| input.into())
+        .map_err(|serde_error| {
+            format!(
+                "Got error while deserializing bounding box: {}",
+                serde_error
+            )
+        })
 }
 
 pub fn parse_points(
     input_array: &js_sys::Float32Array,
     input_point_offset: Vec<f64>,
 ) -> Vec<Vec3WithIndex> {
+    create_points(&input_array.to_vec(), input_point_offset)
+}
+
+/// Creates the points from flat xyz positions, which are relative to the point offset
+pub fn create_points(positions: &[f32], input_point_offset: Vec<f64>) -> Vec<Vec3WithIndex> {
     let point_offset = vec3(
         input_point_offset[0],
         input_point_offset[1],
         input_point_offset[2],
     );
 
-    let point_vec = input_array
-        .to_vec()
+    let point_vec = positions
         .chunks(3)
         .enumerate()
         .map(|(i, p)| Vec3WithIndex {
@@ -64,41 +48,41 @@ pub fn parse_points(
     point_vec
 }
 
-const SHAPE_SCALE_FACTOR: f64 = 1.15;
-const MAX_RADIUS_INCREASE_METER: f64 = 0.06;
-
-fn create_cylinder(input: InputCylinder, id: u16) -> Box<shapes::Cylinder> {
-    let radius = (input.radius * SHAPE_SCALE_FACTOR).min(input.radius + MAX_RADIUS_INCREASE_METER);
-    Box::new(shapes::Cylinder::new(
-        vec3(input.center_a[0], input.center_a[1], input.center_a[2]),
-        vec3(input.center_b[0], input.center_b[1], input.center_b[2]),
-        radius,
-        id,
-    ))
+pub fn try_parse_ept_layout(
+    input_layout: wasm_bindgen::prelude::JsValue,
+) -> Result<decoding::EptLayout, String> {
+    serde_wasm_bindgen::from_value::<InputEptLayout>(input_layout)
+        .map(Into::into)
+        .map_err(|serde_error| format!("Got error while deserializing EPT layout: {}", serde_error))
 }
 
-fn create_box(input: InputOrientedBox, id: u16) -> Box<shapes::OrientedBox> {
-    let matrix = DMat4::from_column_slice(&input.inv_instance_matrix);
-    let scaled_matrix = scaling(&vec3(
-        1.0 / SHAPE_SCALE_FACTOR,
-        1.0 / SHAPE_SCALE_FACTOR,
-        1.0 / SHAPE_SCALE_FACTOR,
-    )) * matrix;
-    Box::new(shapes::OrientedBox::new(scaled_matrix, id))
+/// Parses the minimum corner of the EPT node, which decoded positions are made relative to
+pub fn try_parse_mins(input_mins: Vec<f64>) -> Result<DVec3, String> {
+    match input_mins[..] {
+        [x, y, z] => Ok(vec3(x, y, z)),
+        _ => Err(format!(
+            "Expected three components for the node minimum, got {}",
+            input_mins.len()
+        )),
+    }
 }
 
-fn create_shape(obj: InputShape) -> Result<Box<dyn shapes::Shape>, String> {
-    if let Some(input_cylinder) = obj.cylinder {
-        Ok(create_cylinder(*input_cylinder, obj.object_id))
-    } else if let Some(input_box) = obj.oriented_box {
-        Ok(create_box(*input_box, obj.object_id))
-    } else {
-        Err("Unrecognized geometry type found while parsing".to_string())
-    }
+/// Parses the tolerance used for shapes without their own, which is `Tolerance::default()`
+/// if not given
+pub fn try_parse_tolerance(
+    input_tolerance: wasm_bindgen::prelude::JsValue,
+) -> Result<shapes::Tolerance, String> {
+    let tolerance = serde_wasm_bindgen::from_value::<Option<InputTolerance>>(input_tolerance)
+        .map_err(|serde_error| format!("Got error while deserializing tolerance: {}", serde_error))?
+        .map_or_else(Default::default, Into::into);
+
+    inputs::validate_tolerance(&tolerance)?;
+    Ok(tolerance)
 }
 
 pub fn try_parse_objects(
     input_objects: Vec<wasm_bindgen::prelude::JsValue>,
+    default_tolerance: &shapes::Tolerance,
 ) -> Result<Vec<Box<dyn shapes::Shape>>, String> {
     let objects_result: Result<_, _> = input_objects
         .into_iter()
@@ -108,9 +92,33 @@ pub fn try_parse_objects(
                     format!("Got error while deserializing shape: {}", serde_error)
                 });
 
-            create_shape(input_shape?)
+            inputs::create_shape(input_shape?, default_tolerance)
         })
         .collect();
 
     objects_result
 }
+
+/// Parses the objects as modelled, ignoring all tolerances. Used for measuring distances
+/// to the surfaces of the shapes
+pub fn try_parse_exact_objects(
+    input_objects: &[wasm_bindgen::prelude::JsValue],
+) -> Result<Vec<Box<dyn shapes::Shape>>, String> {
+    let exact_tolerance = shapes::Tolerance {
+        relative_factor: None,
+        absolute_margin: None,
+    };
+
+    input_objects
+        .iter()
+        .map(|input_object| {
+            let mut input_shape =
+                serde_wasm_bindgen::from_value::<InputShape>(input_object.clone()).map_err(
+                    |serde_error| format!("Got error while deserializing shape: {}", serde_error),
+                )?;
+            inputs::remove_tolerances(&mut input_shape);
+
+            inputs::create_shape(input_shape, &exact_tolerance)
+        })
+        .collect()
+}